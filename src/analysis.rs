@@ -0,0 +1,287 @@
+use std::fmt::Display;
+
+use crate::{
+    error::PicrossError,
+    game_board::{GameBoard, GameBoardRow, TileState},
+    iterators::PicrossLineIter,
+    picross::PicrossGame,
+    sat::{
+        cdcl::{CdclSolver, SatResult, SatStatistics},
+        cnf::{board_from_model, encode_game},
+    },
+};
+
+/// up to `limit` distinct solutions of the puzzle, each found by solving again
+/// with a clause ruling out the solutions found before it
+pub fn find_solutions(game: &PicrossGame, limit: usize) -> Vec<GameBoard> {
+    let (width, height) = (game.width(), game.height());
+    let mut solver = CdclSolver::new(&encode_game(game));
+    let mut solutions = vec![];
+    while solutions.len() < limit {
+        let SatResult::Satisfiable(model) = solver.solve() else {
+            break;
+        };
+        // the tile variables come first, so negating them blocks this board
+        let blocking: Vec<i32> = model
+            .iter()
+            .take(width * height)
+            .map(|literal| -literal)
+            .collect();
+        solutions.push(board_from_model(width, height, &model));
+        if blocking.is_empty() {
+            break;
+        }
+        solver.add_clause(&blocking);
+    }
+    solutions
+}
+
+/// how many solutions a puzzle has
+#[derive(Debug, PartialEq)]
+pub enum Uniqueness {
+    /// no board fits the clues
+    Unsolvable,
+    /// the only board that fits the clues
+    Unique(GameBoard),
+    /// two of the solutions, there may be more
+    Multiple(GameBoard, GameBoard),
+}
+
+/// whether the puzzle has no solution, exactly one or more than one
+pub fn check_uniqueness(game: &PicrossGame) -> Uniqueness {
+    let mut solutions = find_solutions(game, 2).into_iter();
+    match (solutions.next(), solutions.next()) {
+        (None, _) => Uniqueness::Unsolvable,
+        (Some(solution), None) => Uniqueness::Unique(solution),
+        (Some(first), Some(second)) => Uniqueness::Multiple(first, second),
+    }
+}
+
+/// line solves every row and column of `board` until a sweep over all of
+/// them changes nothing, returning the board and the number of sweeps that
+/// changed a tile, or a `Contradiction` when a line has no arrangement left
+pub(crate) fn line_solve(
+    game: &PicrossGame,
+    board: &GameBoard,
+) -> Result<(GameBoard, usize), PicrossError> {
+    if game.width() != board.width() || game.height() != board.height() {
+        return Err(PicrossError::DimensionMismatch {
+            expected: (game.width(), game.height()),
+            found: (board.width(), board.height()),
+        });
+    }
+    let mut board = board.clone();
+    let mut sweeps = 0;
+    loop {
+        let mut changed = false;
+        for (row_index, rule) in game.rows.0.iter().enumerate() {
+            let row = &board.0[row_index];
+            let solved =
+                PicrossLineIter::new(&rule.0, game.width()).get_partially_solved_line(Some(row))?;
+            if solved != *row {
+                changed = true;
+                board.0[row_index] = solved;
+            }
+        }
+        for (column_index, rule) in game.columns.0.iter().enumerate() {
+            let column = GameBoardRow(board.get_column(column_index)?);
+            let solved = PicrossLineIter::new(&rule.0, game.height())
+                .get_partially_solved_line(Some(&column))?;
+            if solved != column {
+                changed = true;
+                for (row_index, tile) in solved.0.into_iter().enumerate() {
+                    board.set_tile(column_index, row_index, tile)?;
+                }
+            }
+        }
+        if !changed {
+            return Ok((board, sweeps));
+        }
+        sweeps += 1;
+    }
+}
+
+/// the indices of the rows and of the columns of `board` that no arrangement
+/// of their clue fits any more, each checked on its own
+pub fn broken_lines(
+    game: &PicrossGame,
+    board: &GameBoard,
+) -> Result<(Vec<usize>, Vec<usize>), PicrossError> {
+    if game.width() != board.width() || game.height() != board.height() {
+        return Err(PicrossError::DimensionMismatch {
+            expected: (game.width(), game.height()),
+            found: (board.width(), board.height()),
+        });
+    }
+    let broken = |rule: &[usize], line: &GameBoardRow| {
+        let solved = PicrossLineIter::new(rule, line.0.len()).get_partially_solved_line(Some(line));
+        matches!(solved, Err(PicrossError::Contradiction(_)))
+    };
+    let rows = (0..game.height())
+        .filter(|&index| broken(&game.rows.0[index].0, &board.0[index]))
+        .collect();
+    let mut columns = vec![];
+    for (index, rule) in game.columns.0.iter().enumerate() {
+        if broken(&rule.0, &GameBoardRow(board.get_column(index)?)) {
+            columns.push(index);
+        }
+    }
+    Ok((rows, columns))
+}
+
+/// how hard a puzzle is to solve by hand
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Difficulty {
+    /// line solving finishes within a couple of sweeps
+    Easy,
+    /// line solving finishes, but needs lines revisited many times
+    Medium,
+    /// needs guessing, which the SAT solver settles with few conflicts
+    Hard,
+    /// needs guessing that runs into many conflicts
+    Expert,
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let output = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        };
+        write!(f, "{}", output)
+    }
+}
+
+/// sweeps of line solving that still count as easy
+const EASY_SWEEPS: usize = 3;
+/// SAT conflicts that still count as hard rather than expert
+const HARD_CONFLICTS: usize = 100;
+
+/// what `rate` found out about a puzzle
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    /// the overall verdict, from the sweeps and the SAT conflicts
+    pub difficulty: Difficulty,
+    /// sweeps over every line that line solving made progress in
+    pub sweeps: usize,
+    /// tiles line solving determined, out of `width * height`
+    pub line_solved_tiles: usize,
+    /// whether no other board fits the clues
+    pub unique: bool,
+    /// the SAT solver's effort finding the first solution
+    pub statistics: SatStatistics,
+}
+
+/// rates a puzzle by line solving it first and handing what is left to the
+/// SAT solver
+pub fn rate(game: &PicrossGame) -> Result<Rating, PicrossError> {
+    let empty_board = GameBoard::new(game.width(), game.height());
+    let (board, sweeps) = line_solve(game, &empty_board)?;
+    let line_solved_tiles = board
+        .0
+        .iter()
+        .flat_map(|row| row.0.iter())
+        .filter(|tile| **tile != TileState::Undetermined)
+        .count();
+
+    let mut solver = CdclSolver::new(&encode_game(game));
+    if solver.solve() == SatResult::Unsatisfiable {
+        return Err(PicrossError::Contradiction(
+            "the puzzle has no solution".to_string(),
+        ));
+    }
+    let statistics = solver.statistics.clone();
+    let unique = find_solutions(game, 2).len() == 1;
+
+    let difficulty = match (line_solved_tiles == game.width() * game.height(), sweeps) {
+        (true, sweeps) if sweeps <= EASY_SWEEPS => Difficulty::Easy,
+        (true, _) => Difficulty::Medium,
+        (false, _) if statistics.conflicts <= HARD_CONFLICTS => Difficulty::Hard,
+        (false, _) => Difficulty::Expert,
+    };
+    Ok(Rating {
+        difficulty,
+        sweeps,
+        line_solved_tiles,
+        unique,
+        statistics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_solutions() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        assert_eq!(find_solutions(&game, 5).len(), 1);
+        assert!(matches!(check_uniqueness(&game), Uniqueness::Unique(_)));
+
+        // a 2x2 diagonal can go either way
+        let game = PicrossGame::from_rules("1,1", "1,1").unwrap();
+        assert_eq!(find_solutions(&game, 5).len(), 2);
+        let Uniqueness::Multiple(first, second) = check_uniqueness(&game) else {
+            panic!("the diagonal puzzle has two solutions");
+        };
+        assert_ne!(first, second);
+
+        let game = PicrossGame::from_rules("2,0", "0,2").unwrap();
+        assert_eq!(check_uniqueness(&game), Uniqueness::Unsolvable);
+    }
+
+    #[test]
+    fn test_line_solve() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let (board, sweeps) = line_solve(&game, &GameBoard::new(3, 3)).unwrap();
+        assert_eq!(board.render(), "██  ██\n  ██  \n██  ██");
+        assert!(sweeps >= 1);
+
+        let game = PicrossGame::from_rules("1,1", "1,1").unwrap();
+        let (board, sweeps) = line_solve(&game, &GameBoard::new(2, 2)).unwrap();
+        assert_eq!(board, GameBoard::new(2, 2));
+        assert_eq!(sweeps, 0);
+
+        let game = PicrossGame::from_rules("2,0", "0,2").unwrap();
+        assert!(matches!(
+            line_solve(&game, &GameBoard::new(2, 2)),
+            Err(PicrossError::Contradiction(_))
+        ));
+        assert!(matches!(
+            line_solve(&game, &GameBoard::new(3, 2)),
+            Err(PicrossError::DimensionMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_broken_lines() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let mut board = GameBoard::new(3, 3);
+        assert_eq!(broken_lines(&game, &board).unwrap(), (vec![], vec![]));
+        board.set_tile(0, 0, TileState::Filled).unwrap();
+        board.set_tile(1, 0, TileState::Filled).unwrap();
+        board.set_tile(1, 1, TileState::Filled).unwrap();
+        board.set_tile(2, 1, TileState::Empty).unwrap();
+        // the first row and middle column can't hold two filled tiles side by
+        // side, the middle row still fits its clue
+        assert_eq!(broken_lines(&game, &board).unwrap(), (vec![0], vec![1]));
+        assert!(broken_lines(&game, &GameBoard::new(2, 3)).is_err());
+    }
+
+    #[test]
+    fn test_rate() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let rating = rate(&game).unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+        assert_eq!(rating.line_solved_tiles, 9);
+        assert!(rating.unique);
+
+        let game = PicrossGame::from_rules("1,1", "1,1").unwrap();
+        let rating = rate(&game).unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Hard);
+        assert_eq!(rating.line_solved_tiles, 0);
+        assert!(!rating.unique);
+    }
+}
@@ -1,75 +1,182 @@
-use std::{fs::read_to_string, time::Instant};
+use std::fs::{read_to_string, write};
 
-use crate::{
-    picross::{
-        picross_solver_v1::PicrossSolverV1, picross_solver_v2::PicrossSolverV2,
-        picross_solver_v3::PicrossSolverV3, PicrossGame, PicrossSolver,
-    },
-    render::PicrossFrame,
+use picross_solver::{
+    board_from_model, parse_model, render_board_png, render_frame_png, render_html, render_svg,
+    search_path, split_selector, FrameObserver, GameBoard, GameState, GifOptions, GifRecorder,
+    ImageOptions, Input, InputFormat, PicrossError, PicrossFrame, PicrossGame, PicrossSolver,
+    PicrossSolverV1, PicrossSolverV2, PicrossSolverV3, PicrossSolverV4, PuzzleCollection,
+    SolveReport, SvgOptions,
 };
 
 pub enum SolverVersion {
     One(PicrossSolverV1),
     Two(PicrossSolverV2),
     Three(PicrossSolverV3),
+    Four(PicrossSolverV4),
 }
 
 impl SolverVersion {
-    fn solve(&mut self) -> Result<PicrossFrame, &str> {
+    pub fn name(&self) -> &'static str {
         match self {
-            SolverVersion::One(solver) => solver.solve(),
-            SolverVersion::Two(solver) => solver.solve(),
-            SolverVersion::Three(solver) => solver.solve(),
+            SolverVersion::One(_) => "v1",
+            SolverVersion::Two(_) => "v2",
+            SolverVersion::Three(_) => "v3",
+            SolverVersion::Four(_) => "v4",
         }
     }
+}
+
+impl PicrossSolver for SolverVersion {
+    fn solve_with_observer(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<PicrossFrame, PicrossError> {
+        match self {
+            SolverVersion::One(solver) => solver.solve_with_observer(observer),
+            SolverVersion::Two(solver) => solver.solve_with_observer(observer),
+            SolverVersion::Three(solver) => solver.solve_with_observer(observer),
+            SolverVersion::Four(solver) => solver.solve_with_observer(observer),
+        }
+    }
+    fn solve_with_report(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<SolveReport, PicrossError> {
+        match self {
+            SolverVersion::One(solver) => solver.solve_with_report(observer),
+            SolverVersion::Two(solver) => solver.solve_with_report(observer),
+            SolverVersion::Three(solver) => solver.solve_with_report(observer),
+            SolverVersion::Four(solver) => solver.solve_with_report(observer),
+        }
+    }
+    fn from_game(game: PicrossGame) -> Self {
+        SolverVersion::Three(PicrossSolverV3::from_game(game))
+    }
     fn set_game(&mut self, game: PicrossGame) {
         match self {
             SolverVersion::One(solver) => solver.set_game(game),
             SolverVersion::Two(solver) => solver.set_game(game),
             SolverVersion::Three(solver) => solver.set_game(game),
+            SolverVersion::Four(solver) => solver.set_game(game),
         }
     }
 }
 
+fn game_not_set(message: &str) -> PicrossError {
+    PicrossError::Validation(message.to_string())
+}
+
 pub struct App {
     pub version: SolverVersion,
     game: Option<PicrossGame>,
+    /// draw lines between every 5 rows and columns of the solved board
+    guides: bool,
 }
 
 impl App {
     pub fn new() -> Self {
         let default_game = PicrossGame::default();
         App {
-            version: SolverVersion::Three(PicrossSolverV3::from_game(default_game)),
+            version: SolverVersion::from_game(default_game),
             game: None,
+            guides: false,
         }
     }
-    pub fn solve(&mut self) -> Result<(), String> {
+    pub fn game(&self) -> Option<&PicrossGame> {
+        self.game.as_ref()
+    }
+    pub fn solve_with_observer(
+        &mut self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<SolveReport, PicrossError> {
         let game = self
             .game
             .as_ref()
-            .ok_or("Picross Game not set prior to solving")?
+            .ok_or_else(|| game_not_set("Picross Game not set prior to solving"))?
             .clone();
         self.version.set_game(game);
-        println!(
-            "{}{}Solving Started...",
+        observer.on_text(&format!(
+            "{}{}Solving Started...\n",
             termion::clear::All,
             termion::cursor::Goto(1, 1),
-        );
-        let start = Instant::now();
-        let result = self.version.solve()?;
-        let duration = start.elapsed();
-        let rendered_result = result.render();
-        println!(
-            "{}{}{}\n\nElapsed time: {:?}",
+        ));
+        let report = self.version.solve_with_report(observer)?;
+        observer.on_text(&format!(
+            "{}{}{}\n\nElapsed time: {:?}\n",
             termion::clear::All,
             termion::cursor::Goto(1, 1),
-            rendered_result,
-            duration
-        );
-        Ok(())
+            report.frame.render_with_guides(self.guides),
+            report.elapsed
+        ));
+        Ok(report)
+    }
+    pub fn export_svg(&self, path: &str, solution: Option<&GameBoard>) -> Result<(), PicrossError> {
+        let game = self
+            .game
+            .as_ref()
+            .ok_or_else(|| game_not_set("Picross Game not set prior to exporting"))?;
+        let svg = render_svg(game, solution, &SvgOptions::default());
+        write(path, svg).map_err(|error| PicrossError::io(path, error))
+    }
+    pub fn export_html(
+        &self,
+        path: &str,
+        solution: &GameBoard,
+        title: &str,
+    ) -> Result<(), PicrossError> {
+        let game = self
+            .game
+            .as_ref()
+            .ok_or_else(|| game_not_set("Picross Game not set prior to exporting"))?;
+        let html = render_html(game, solution, title)?;
+        write(path, html).map_err(|error| PicrossError::io(path, error))
+    }
+    pub fn export_png(
+        &self,
+        path: &str,
+        board: &GameBoard,
+        scale: usize,
+        with_clues: bool,
+    ) -> Result<(), PicrossError> {
+        let png = match with_clues {
+            true => {
+                let game = self
+                    .game
+                    .as_ref()
+                    .ok_or_else(|| game_not_set("Picross Game not set prior to exporting"))?;
+                render_frame_png(game, board, scale)?
+            }
+            false => render_board_png(board, scale),
+        };
+        write(path, png).map_err(|error| PicrossError::io(path, error))
+    }
+    /// builds the board described by a SAT solver's model of `export_dimacs`
+    pub fn read_model(&self, path: &str) -> Result<PicrossFrame, PicrossError> {
+        let game = self
+            .game
+            .as_ref()
+            .ok_or_else(|| game_not_set("Picross Game not set prior to reading a model"))?;
+        let model = read_to_string(path).map_err(|error| PicrossError::io(path, error))?;
+        let board = board_from_model(game.width(), game.height(), &parse_model(&model)?);
+        let game_state = match PicrossGame::from_board(&board) {
+            Ok(board_game) if board_game.same_clues(game) => GameState::Complete,
+            _ => GameState::Invalid,
+        };
+        PicrossFrame::new(game.clone(), board, game_state)
     }
-    pub fn change_version(&mut self, version: &str) -> Result<(), String> {
+    pub fn solve_recording_gif(
+        &mut self,
+        path: &str,
+        options: GifOptions,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<SolveReport, PicrossError> {
+        let mut recorder = GifRecorder::new(options);
+        let report = self.solve_with_observer(&mut vec![observer, &mut recorder])?;
+        let gif = recorder.finish(&report.frame)?;
+        write(path, gif).map_err(|error| PicrossError::io(path, error))?;
+        Ok(report)
+    }
+    pub fn change_version(&mut self, version: &str) -> Result<(), PicrossError> {
         match version {
             "v1" => {
                 self.version =
@@ -83,28 +190,70 @@ impl App {
                 self.version =
                     SolverVersion::Three(PicrossSolverV3(PicrossGame::from_rules("0", "0")?))
             }
+            "v4" => {
+                self.version =
+                    SolverVersion::Four(PicrossSolverV4(PicrossGame::from_rules("0", "0")?))
+            }
             _ => {
-                return Err(
-                    "invalid version selection\n Available versions are: v1, v2, v3".to_string(),
-                )
+                return Err(PicrossError::Validation(
+                    "invalid version selection\n Available versions are: v1, v2, v3, v4"
+                        .to_string(),
+                ))
             }
         }
         Ok(())
     }
-    pub fn select_game_from_text_image(&mut self, text_file_name: &str) -> Result<(), String> {
-        let filepath = format!("./text_images/{}.txt", text_file_name);
-        let text_render =
-            read_to_string(&filepath).map_err(|_| format!("could not read \"{}\"", filepath))?;
-        let game = PicrossGame::from_text_render(&text_render)?;
-        self.game = Some(game);
+    pub fn read_collection(
+        &self,
+        source: &str,
+        format: Option<InputFormat>,
+        default_format: InputFormat,
+        options: &ImageOptions,
+    ) -> Result<PuzzleCollection, PicrossError> {
+        Input::resolve(source, format, default_format, &search_path())?.load(options)
+    }
+    /// the puzzles `source` names, which is a path, "-" for stdin or a short
+    /// name found on the search path, all of them unless it ends in `#name` or
+    /// `#index` to pick one out of a collection
+    pub fn read_puzzles(
+        &self,
+        source: &str,
+        format: Option<InputFormat>,
+        default_format: InputFormat,
+        options: &ImageOptions,
+    ) -> Result<PuzzleCollection, PicrossError> {
+        let (file_name, selector) = split_selector(source);
+        let collection = self.read_collection(file_name, format, default_format, options)?;
+        match selector {
+            Some(selector) => Ok(PuzzleCollection(vec![collection.select(selector)?.clone()])),
+            None => Ok(collection),
+        }
+    }
+    /// selects the single puzzle `read_puzzles` finds for `source`
+    pub fn select_game(
+        &mut self,
+        source: &str,
+        format: Option<InputFormat>,
+        default_format: InputFormat,
+        options: &ImageOptions,
+    ) -> Result<(), PicrossError> {
+        let mut collection = self.read_puzzles(source, format, default_format, options)?;
+        match collection.len() {
+            1 => self.game = collection.0.pop().map(|entry| entry.game),
+            count => {
+                let (file_name, _) = split_selector(source);
+                return Err(PicrossError::Validation(format!(
+                    "\"{}\" holds {} puzzles, pick one with {}#name or {}#index",
+                    file_name, count, file_name, file_name
+                )));
+            }
+        }
         Ok(())
     }
-    pub fn select_game_from_puzzles(&mut self, puzzle_name: &str) -> Result<(), String> {
-        let filepath = format!("./puzzles/{}.pic", puzzle_name);
-        let rules_file_content =
-            read_to_string(&filepath).map_err(|_| format!("could not read \"{}\"", filepath))?;
-        let game = PicrossGame::from_rules_file_string(&rules_file_content)?;
+    pub fn set_game(&mut self, game: PicrossGame) {
         self.game = Some(game);
-        Ok(())
+    }
+    pub fn set_guides(&mut self, guides: bool) {
+        self.guides = guides;
     }
 }
@@ -0,0 +1,245 @@
+use std::fmt::Write;
+
+use crate::{
+    error::{ParseError, PicrossError},
+    picross::{PicrossGame, PicrossSolver, SolveReport},
+    render::FrameObserver,
+};
+
+/// starts a puzzle in a collection file, optionally followed by its name
+pub const SEPARATOR: &str = "====";
+
+/// a puzzle in a collection and the name it goes by
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionEntry {
+    /// the name from the header, or the puzzle's 1-based position
+    pub name: String,
+    /// the puzzle's clues
+    pub game: PicrossGame,
+}
+
+/// a pack of puzzles, each one a `.pic` puzzle under a `==== name` header,
+/// a file without headers holds a single puzzle named "1", `#` comments
+/// before the first header describe the pack and are skipped
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PuzzleCollection(pub Vec<CollectionEntry>);
+
+impl PuzzleCollection {
+    /// parses a collection file, a file without headers gives a collection of
+    /// one
+    pub fn from_collection_string(input: &str) -> Result<Self, PicrossError> {
+        // each section holds its name, the number of lines before it and its lines
+        let mut sections: Vec<(Option<String>, usize, Vec<&str>)> = vec![(None, 0, vec![])];
+        for (index, line) in input.lines().enumerate() {
+            match line.trim().strip_prefix(SEPARATOR) {
+                Some(header) => {
+                    let name = header.trim().trim_end_matches('=').trim();
+                    sections.push((Some(name.to_string()), index + 1, vec![]));
+                }
+                None => sections.last_mut().unwrap().2.push(line),
+            }
+        }
+        if sections.len() > 1 {
+            let (_, _, preamble) = sections.remove(0);
+            let puzzle_line = preamble.iter().enumerate().find(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#')
+            });
+            if let Some((index, line)) = puzzle_line {
+                let column = line.chars().take_while(|char| char.is_whitespace()).count() + 1;
+                let token = line.trim();
+                let expected = format!("a \"{} name\" header first", SEPARATOR);
+                return Err(ParseError::new(line, index + 1, column, token, &expected).into());
+            }
+        }
+
+        let has_headers = sections[0].0.is_some();
+        let mut collection = PuzzleCollection::default();
+        for (index, (name, line_offset, lines)) in sections.into_iter().enumerate() {
+            let name = match name {
+                Some(name) if !name.is_empty() => name,
+                _ => (index + 1).to_string(),
+            };
+            if collection.get(&name).is_some() {
+                return Err(PicrossError::Validation(format!(
+                    "puzzle \"{}\" appears more than once",
+                    name
+                )));
+            }
+            let game = PicrossGame::from_rules_file_lines(&lines.join("\n"), line_offset).map_err(
+                |error| match has_headers {
+                    true => error.with_context(format!("puzzle \"{}\"", name)),
+                    false => error,
+                },
+            )?;
+            collection.0.push(CollectionEntry { name, game });
+        }
+        Ok(collection)
+    }
+
+    /// every puzzle under its own header, ready to be read back
+    pub fn to_collection_string(&self) -> String {
+        let mut output = String::new();
+        for entry in self.0.iter() {
+            let _ = writeln!(
+                output,
+                "{} {}\n{}",
+                SEPARATOR,
+                entry.name,
+                entry.game.to_rules_file_string()
+            );
+        }
+        output
+    }
+
+    /// the number of puzzles
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// true when there are no puzzles
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// the puzzles in file order
+    pub fn iter(&self) -> impl Iterator<Item = &CollectionEntry> {
+        self.0.iter()
+    }
+
+    /// finds a puzzle by name
+    pub fn get(&self, name: &str) -> Option<&CollectionEntry> {
+        self.0.iter().find(|entry| entry.name == name)
+    }
+
+    /// solves every puzzle in order with `solver`, pairing each puzzle's name
+    /// with its report or the error solving it
+    pub fn solve_all<S: PicrossSolver>(
+        &self,
+        solver: &mut S,
+        observer: &mut dyn FrameObserver,
+    ) -> Vec<(&str, Result<SolveReport, PicrossError>)> {
+        self.0
+            .iter()
+            .map(|entry| {
+                solver.set_game(entry.game.clone());
+                (entry.name.as_str(), solver.solve_with_report(observer))
+            })
+            .collect()
+    }
+
+    /// finds a puzzle by name, falling back to its 1-based position
+    pub fn select(&self, selector: &str) -> Result<&CollectionEntry, PicrossError> {
+        self.get(selector)
+            .or_else(|| {
+                let index = selector.parse::<usize>().ok()?;
+                self.0.get(index.checked_sub(1)?)
+            })
+            .ok_or_else(|| {
+                PicrossError::Validation(format!(
+                    "no puzzle \"{}\" in collection, available puzzles are: {}",
+                    selector,
+                    self.0
+                        .iter()
+                        .map(|entry| entry.name.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{picross::picross_solver_v4::PicrossSolverV4, render::GameState};
+
+    const PACK: &str = "\
+# a test pack
+==== cross
+1
+3
+1
+-----
+1
+3
+1
+====
+1
+-----
+1
+==== corners ====
+1 1,0,1 1
+-----
+1 1,0,1 1
+";
+
+    #[test]
+    fn test_from_collection_string() {
+        let collection = PuzzleCollection::from_collection_string(PACK).unwrap();
+        let names: Vec<&str> = collection.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, vec!["cross", "2", "corners"]);
+        assert_eq!(
+            collection.get("cross").unwrap().game,
+            PicrossGame::from_rules("1,3,1", "1,3,1").unwrap()
+        );
+        assert_eq!(collection.select("corners").unwrap().name, "corners");
+        assert_eq!(collection.select("1").unwrap().name, "cross");
+        assert_eq!(collection.select("2").unwrap().name, "2");
+        assert!(collection.select("4").is_err());
+        assert!(collection.select("0").is_err());
+    }
+
+    #[test]
+    fn test_single_puzzle_file() {
+        let collection = PuzzleCollection::from_collection_string("1\n-----\n1\n").unwrap();
+        assert_eq!(collection.len(), 1);
+        assert_eq!(collection.select("1").unwrap().name, "1");
+    }
+
+    #[test]
+    fn test_invalid_collections() {
+        assert!(
+            PuzzleCollection::from_collection_string("1\n-----\n1\n==== a\n1\n-----\n1").is_err()
+        );
+        assert!(PuzzleCollection::from_collection_string(
+            "==== a\n1\n-----\n1\n==== a\n1\n-----\n1"
+        )
+        .is_err());
+        let error = PuzzleCollection::from_collection_string("==== a\n1\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("puzzle \"a\": line 3, column 1"));
+        let error =
+            PuzzleCollection::from_collection_string("==== a\n1\n-----\n1\n==== b\n1\n-----\n?")
+                .unwrap_err()
+                .to_string();
+        assert!(error.starts_with("puzzle \"b\": line 8, column 1: found \"?\""));
+    }
+
+    #[test]
+    fn test_collection_round_trip() {
+        let collection = PuzzleCollection::from_collection_string(PACK).unwrap();
+        let output = collection.to_collection_string();
+        assert!(output.starts_with("==== cross\n1\n3\n1\n-----\n"));
+        assert_eq!(
+            PuzzleCollection::from_collection_string(&output).unwrap(),
+            collection
+        );
+    }
+
+    #[test]
+    fn test_solve_all() {
+        let collection = PuzzleCollection::from_collection_string(PACK).unwrap();
+        let mut solver = PicrossSolverV4::from_game(PicrossGame::default());
+        let results = collection.solve_all(&mut solver, &mut Vec::new());
+        let names: Vec<&str> = results.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["cross", "2", "corners"]);
+        for (_, result) in results {
+            let report = result.unwrap();
+            assert_eq!(report.frame.game_state, GameState::Complete);
+            assert_eq!(report.statistics[0].0, "frames");
+            assert!(report.statistics.iter().any(|(key, _)| *key == "conflicts"));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::{fmt, io};
+
+/// every way working with a puzzle can fail
+#[derive(Debug)]
+pub enum PicrossError {
+    /// puzzle text that doesn't follow the expected syntax
+    Parse(ParseError),
+    /// a file that isn't in the binary or text format it should be in
+    Format(String),
+    /// well formed input that can't be used, such as rules that don't add up
+    Validation(String),
+    /// a board whose size doesn't match the puzzle, as (width, height)
+    DimensionMismatch {
+        /// the size of the puzzle
+        expected: (usize, usize),
+        /// the size of the board
+        found: (usize, usize),
+    },
+    /// tiles that no arrangement of the clues can produce
+    Contradiction(String),
+    /// a file that couldn't be read or written
+    Io {
+        /// the file, as given
+        path: String,
+        /// what the operating system reported
+        source: io::Error,
+    },
+    /// another error along with where it happened, such as a file or puzzle
+    Context {
+        /// what was being worked on, such as `puzzle "cat"`
+        context: String,
+        /// the error that happened there
+        source: Box<PicrossError>,
+    },
+}
+
+impl PicrossError {
+    /// an `Io` error for the file at `path`
+    pub fn io(path: &str, source: io::Error) -> Self {
+        Self::Io {
+            path: path.to_string(),
+            source,
+        }
+    }
+
+    /// wraps the error in a `Context` saying where it happened
+    pub fn with_context(self, context: impl Into<String>) -> Self {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl fmt::Display for PicrossError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PicrossError::Parse(error) => write!(f, "{}", error),
+            PicrossError::Format(message)
+            | PicrossError::Validation(message)
+            | PicrossError::Contradiction(message) => write!(f, "{}", message),
+            PicrossError::DimensionMismatch { expected, found } => write!(
+                f,
+                "expected a {}x{} board, found {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            PicrossError::Io { path, source } => write!(f, "\"{}\": {}", path, source),
+            PicrossError::Context { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for PicrossError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PicrossError::Parse(error) => Some(error),
+            PicrossError::Io { source, .. } => Some(source),
+            PicrossError::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for PicrossError {
+    fn from(error: ParseError) -> Self {
+        PicrossError::Parse(error)
+    }
+}
+
+/// where and why a puzzle file failed to parse, lines and columns start at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// the line the error is on
+    pub line: usize,
+    /// the column the offending token starts at, in characters
+    pub column: usize,
+    /// the text that couldn't be parsed
+    pub token: String,
+    /// what should have been there instead
+    pub expected: String,
+    /// the full text of the offending line, used for the snippet
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// a parse error for `token` at `line` and `column` of `source_line`
+    pub fn new(source_line: &str, line: usize, column: usize, token: &str, expected: &str) -> Self {
+        Self {
+            line,
+            column,
+            token: token.to_string(),
+            expected: expected.to_string(),
+            source_line: source_line.to_string(),
+        }
+    }
+
+    /// the offending line with a caret under the token
+    pub fn snippet(&self) -> String {
+        let gutter = self.line.to_string().len();
+        let underline = "^".repeat(self.token.chars().count().max(1));
+        format!(
+            "{:>gutter$} | {}\n{:>gutter$} | {}{}",
+            self.line,
+            self.source_line,
+            "",
+            " ".repeat(self.column.saturating_sub(1)),
+            underline,
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let found = match self.token.is_empty() {
+            true => "end of line".to_string(),
+            false => format!("\"{}\"", self.token),
+        };
+        write!(
+            f,
+            "line {}, column {}: found {}, expected {}\n{}",
+            self.line,
+            self.column,
+            found,
+            self.expected,
+            self.snippet()
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_error_source_chain() {
+        let parse_error = ParseError::new("1 x", 2, 3, "x", "a clue number");
+        let error = PicrossError::from(parse_error.clone()).with_context("pack.pic");
+        assert!(error
+            .to_string()
+            .starts_with("pack.pic: line 2, column 3: found \"x\""));
+        let source = error.source().unwrap();
+        assert!(
+            matches!(source.downcast_ref(), Some(PicrossError::Parse(inner)) if *inner == parse_error)
+        );
+
+        let error = PicrossError::io("missing.pic", io::Error::from(io::ErrorKind::NotFound));
+        assert!(error.to_string().starts_with("\"missing.pic\": "));
+        assert!(error
+            .source()
+            .unwrap()
+            .downcast_ref::<io::Error>()
+            .is_some());
+
+        let error = PicrossError::DimensionMismatch {
+            expected: (2, 3),
+            found: (3, 2),
+        };
+        assert_eq!(error.to_string(), "expected a 2x3 board, found 3x2");
+    }
+
+    #[test]
+    fn test_display() {
+        let error = ParseError::new("1 x2 3", 12, 3, "x2", "a clue number");
+        assert_eq!(
+            error.to_string(),
+            "line 12, column 3: found \"x2\", expected a clue number\n12 | 1 x2 3\n   |   ^^"
+        );
+        let error = ParseError::new("", 5, 1, "", "a clue number");
+        assert_eq!(
+            error.to_string(),
+            "line 5, column 1: found end of line, expected a clue number\n5 | \n  | ^"
+        );
+    }
+}
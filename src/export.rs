@@ -0,0 +1,39 @@
+use std::fmt::Write;
+
+pub(crate) mod asciicast;
+pub(crate) mod gif;
+pub(crate) mod html;
+pub(crate) mod json;
+pub(crate) mod png;
+pub(crate) mod svg;
+
+/// escapes `input` for use inside a JSON string literal
+pub(crate) fn escape_json(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for char in input.chars() {
+        match char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            char if (char as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", char as u32);
+            }
+            char => output.push(char),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("a\"b\\c"), "a\\\"b\\\\c");
+        assert_eq!(escape_json("\x1b[2J\n"), "\\u001b[2J\\n");
+        assert_eq!(escape_json("██"), "██");
+    }
+}
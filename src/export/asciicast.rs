@@ -0,0 +1,83 @@
+use std::{
+    fmt::Write,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::export::escape_json;
+
+/// records what a `PacedPrinter` writes to the terminal as asciicast v2
+pub struct AsciicastRecorder {
+    width: u16,
+    height: u16,
+    start: Instant,
+    timestamp: u64,
+    events: Vec<(f64, String)>,
+}
+
+impl AsciicastRecorder {
+    /// a recording for a terminal of `width` columns and `height` rows,
+    /// timed from now
+    pub fn new(width: u16, height: u16) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        Self {
+            width,
+            height,
+            start: Instant::now(),
+            timestamp,
+            events: vec![],
+        }
+    }
+
+    /// adds `output` as written at the current time
+    pub fn record(&mut self, output: &str) {
+        // the tty turns "\n" into "\r\n" before it reaches the terminal,
+        // players replay the stream without that translation
+        let output = output.replace("\r\n", "\n").replace('\n', "\r\n");
+        self.events
+            .push((self.start.elapsed().as_secs_f64(), output));
+    }
+
+    /// the header line followed by an event line per recorded write
+    pub fn to_cast_string(&self) -> String {
+        let mut cast = format!(
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}, \"env\": {{\"TERM\": \"xterm-256color\"}}}}\n",
+            self.width, self.height, self.timestamp
+        );
+        for (time, output) in self.events.iter() {
+            let _ = writeln!(cast, "[{:.6}, \"o\", \"{}\"]", time, escape_json(output));
+        }
+        cast
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_board::{GameBoard, GameBoardRow, TileState::*},
+        picross::PicrossGame,
+        render::{AnimationOptions, FrameObserver, GameState, PacedPrinter, PicrossFrame},
+    };
+
+    #[test]
+    fn test_cast_output() {
+        let recorder = AsciicastRecorder::new(80, 24);
+        let mut printer = PacedPrinter::recording(AnimationOptions::default(), recorder, false);
+        printer.on_text("Solving Started...\n");
+        let game = PicrossGame::from_rules("1", "1").unwrap();
+        let board = GameBoard(vec![GameBoardRow(vec![Filled])]);
+        let frame = PicrossFrame::new(game, board, GameState::Complete).unwrap();
+        printer.on_frame(&frame, true);
+
+        let cast = printer.into_recording().unwrap().to_cast_string();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 80, \"height\": 24,"));
+        assert!(lines[1].ends_with(", \"o\", \"Solving Started...\\r\\n\"]"));
+        assert!(lines[2].contains("\\u001b[2J\\u001b[1;1HComplete"));
+        assert!(lines[2].contains("\\r\\n1██"));
+    }
+}
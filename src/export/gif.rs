@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::PicrossError,
+    game_board::{GameBoard, TileState},
+    render::{FrameObserver, PicrossFrame},
+};
+
+const MIN_CODE_SIZE: u8 = 2;
+const MAX_CODE: u16 = 4096;
+/// undetermined, filled, empty and an unused fourth entry
+const PALETTE: [[u8; 3]; 4] = [[200, 200, 200], [0, 0, 0], [255, 255, 255], [255, 255, 255]];
+/// hold the final frame for two seconds before the animation loops
+const FINAL_FRAME_DELAY: u16 = 200;
+
+/// how `encode_gif` and `GifRecorder` draw and pace the frames
+#[derive(Debug, Clone, PartialEq)]
+pub struct GifOptions {
+    /// pixels per tile
+    pub scale: usize,
+    /// delay between frames in hundredths of a second
+    pub delay: u16,
+    /// number of solver frames to drop after each recorded frame
+    pub frame_skip: usize,
+    /// upper bound on recorded frames, the final frame is always kept
+    pub max_frames: usize,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        Self {
+            scale: 10,
+            delay: 5,
+            frame_skip: 0,
+            max_frames: 500,
+        }
+    }
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bit_count: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: vec![],
+            buffer: 0,
+            bit_count: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bit_count;
+        self.bit_count += size;
+        while self.bit_count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// variable length LZW as used by GIF image data, before sub-block packing
+pub(crate) fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear_code: u16 = 1 << min_code_size;
+    let end_code = clear_code + 1;
+    let mut writer = BitWriter::new();
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut code_size = min_code_size + 1;
+    let mut next_code = end_code + 1;
+
+    writer.write(clear_code, code_size);
+    let mut pixels = indices.iter();
+    let Some(first) = pixels.next() else {
+        writer.write(end_code, code_size);
+        return writer.finish();
+    };
+    let mut prefix = *first as u16;
+    for pixel in pixels {
+        if let Some(code) = dictionary.get(&(prefix, *pixel)) {
+            prefix = *code;
+            continue;
+        }
+        writer.write(prefix, code_size);
+        if next_code < MAX_CODE {
+            dictionary.insert((prefix, *pixel), next_code);
+            // the decoder adds each entry one code later, so grow once the
+            // code just added no longer fits
+            if next_code == 1 << code_size {
+                code_size += 1;
+            }
+            next_code += 1;
+        } else {
+            writer.write(clear_code, code_size);
+            dictionary.clear();
+            code_size = min_code_size + 1;
+            next_code = end_code + 1;
+        }
+        prefix = *pixel as u16;
+    }
+    writer.write(prefix, code_size);
+    writer.write(end_code, code_size);
+    writer.finish()
+}
+
+fn tile_index(tile: &TileState) -> u8 {
+    match tile {
+        TileState::Undetermined => 0,
+        TileState::Filled => 1,
+        TileState::Empty => 2,
+    }
+}
+
+fn board_indices(board: &GameBoard, scale: usize) -> Vec<u8> {
+    let mut indices = Vec::with_capacity(board.width() * board.height() * scale * scale);
+    for row in board.0.iter() {
+        let scaled_row: Vec<u8> = row
+            .0
+            .iter()
+            .flat_map(|tile| std::iter::repeat_n(tile_index(tile), scale))
+            .collect();
+        for _ in 0..scale {
+            indices.extend(&scaled_row);
+        }
+    }
+    indices
+}
+
+fn write_sub_blocks(output: &mut Vec<u8>, data: &[u8]) {
+    for block in data.chunks(255) {
+        output.push(block.len() as u8);
+        output.extend(block);
+    }
+    output.push(0);
+}
+
+/// a board side times the scale, which the gif format stores in 16 bits
+fn gif_size(tiles: usize, scale: usize) -> Result<u16, PicrossError> {
+    tiles
+        .checked_mul(scale)
+        .and_then(|size| u16::try_from(size).ok())
+        .ok_or_else(|| {
+            PicrossError::Validation(format!(
+                "{} tiles at {} pixels each are too many for a gif, which is at most {} pixels across",
+                tiles,
+                scale,
+                u16::MAX
+            ))
+        })
+}
+
+/// encodes the boards as a looping animation
+pub fn encode_gif(boards: &[GameBoard], options: &GifOptions) -> Result<Vec<u8>, PicrossError> {
+    let scale = options.scale.max(1);
+    let (width, height) = match boards.first() {
+        Some(board) => (
+            gif_size(board.width(), scale)?,
+            gif_size(board.height(), scale)?,
+        ),
+        None => (0, 0),
+    };
+
+    let mut output = b"GIF89a".to_vec();
+    output.extend(width.to_le_bytes());
+    output.extend(height.to_le_bytes());
+    // global color table with 2^(1+1) entries and 8 bit color resolution
+    output.extend([0b1111_0001, 0, 0]);
+    for color in PALETTE {
+        output.extend(color);
+    }
+    // loop forever
+    output.extend([0x21, 0xff, 11]);
+    output.extend(b"NETSCAPE2.0");
+    output.extend([3, 1, 0, 0, 0]);
+
+    for (index, board) in boards.iter().enumerate() {
+        let delay = if index + 1 == boards.len() {
+            FINAL_FRAME_DELAY.max(options.delay)
+        } else {
+            options.delay
+        };
+        output.extend([0x21, 0xf9, 4, 0b0000_0100]);
+        output.extend(delay.to_le_bytes());
+        output.extend([0, 0]);
+
+        output.push(0x2c);
+        output.extend([0, 0, 0, 0]);
+        output.extend(width.to_le_bytes());
+        output.extend(height.to_le_bytes());
+        output.push(0);
+        output.push(MIN_CODE_SIZE);
+        write_sub_blocks(
+            &mut output,
+            &lzw_encode(&board_indices(board, scale), MIN_CODE_SIZE),
+        );
+    }
+    output.push(0x3b);
+    Ok(output)
+}
+
+/// records solver frames so the solve can be replayed as a gif
+pub struct GifRecorder {
+    options: GifOptions,
+    boards: Vec<GameBoard>,
+    seen_frames: usize,
+}
+
+impl GifRecorder {
+    /// an empty recording that keeps frames as `options` says
+    pub fn new(options: GifOptions) -> Self {
+        Self {
+            options,
+            boards: vec![],
+            seen_frames: 0,
+        }
+    }
+
+    /// appends the final solver result and encodes the recording
+    pub fn finish(mut self, result: &PicrossFrame) -> Result<Vec<u8>, PicrossError> {
+        let max_frames = self.options.max_frames.max(1);
+        if self.boards.len() >= max_frames {
+            self.boards.truncate(max_frames - 1);
+        }
+        if self.boards.last() != Some(&result.board) {
+            self.boards.push(result.board.clone());
+        }
+        encode_gif(&self.boards, &self.options)
+    }
+}
+
+impl FrameObserver for GifRecorder {
+    fn on_frame(&mut self, frame: &PicrossFrame, _clear: bool) {
+        let is_recorded = self
+            .seen_frames
+            .is_multiple_of(self.options.frame_skip.saturating_add(1));
+        self.seen_frames += 1;
+        if !is_recorded || self.boards.len() >= self.options.max_frames {
+            return;
+        }
+        if self.boards.last() != Some(&frame.board) {
+            self.boards.push(frame.board.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_board::{GameBoardRow, TileState::*},
+        picross::PicrossGame,
+        render::GameState,
+    };
+
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear_code = 1_usize << min_code_size;
+        let end_code = clear_code + 1;
+        let reset = || -> Vec<Vec<u8>> { (0..=end_code).map(|code| vec![code as u8]).collect() };
+        let mut table = reset();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = vec![];
+        let mut position = 0;
+        loop {
+            let mut code = 0;
+            for bit in 0..code_size as usize {
+                let byte = data[(position + bit) / 8];
+                code |= (((byte >> ((position + bit) % 8)) & 1) as usize) << bit;
+            }
+            position += code_size as usize;
+            if code == clear_code {
+                table = reset();
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end_code {
+                return output;
+            }
+            let entry = match &previous {
+                None => table[code].clone(),
+                Some(previous) => {
+                    let entry = match table.get(code) {
+                        Some(entry) => entry.clone(),
+                        None => [previous.clone(), vec![previous[0]]].concat(),
+                    };
+                    if table.len() < MAX_CODE as usize {
+                        table.push([previous.clone(), vec![entry[0]]].concat());
+                    }
+                    entry
+                }
+            };
+            if table.len() == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+            output.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw_round_trip() {
+        let repetitive: Vec<u8> = (0..600).map(|index| (index / 7 % 3) as u8).collect();
+        assert_eq!(lzw_decode(&lzw_encode(&repetitive, 2), 2), repetitive);
+
+        // enough varied input to fill the dictionary and force a clear code
+        let mut state = 12345_u32;
+        let noisy: Vec<u8> = (0..40_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                ((state >> 16) % 3) as u8
+            })
+            .collect();
+        assert_eq!(lzw_decode(&lzw_encode(&noisy, 2), 2), noisy);
+
+        assert_eq!(lzw_decode(&lzw_encode(&[], 2), 2), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_encode_gif_structure() {
+        let board = GameBoard(vec![GameBoardRow(vec![Filled, Empty, Undetermined])]);
+        let gif = encode_gif(&[board.clone(), board], &GifOptions::default()).unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(&gif[6..10], &[30, 0, 10, 0]);
+        assert!(gif.iter().filter(|byte| **byte == 0x2c).count() >= 2);
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        // a side past 65535 pixels doesn't fit the header
+        let options = GifOptions {
+            scale: 30_000,
+            ..Default::default()
+        };
+        let board = GameBoard::new(3, 1);
+        assert!(matches!(
+            encode_gif(&[board], &options),
+            Err(PicrossError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_recorder_skips_and_limits_frames() {
+        let game = PicrossGame::from_rules("1", "1").unwrap();
+        let options = GifOptions {
+            frame_skip: 1,
+            max_frames: 3,
+            ..Default::default()
+        };
+        let mut recorder = GifRecorder::new(options);
+        let tiles = [Undetermined, Filled, Empty, Undetermined, Filled, Empty];
+        for tile in tiles.iter() {
+            let board = GameBoard(vec![GameBoardRow(vec![tile.clone()])]);
+            let frame = PicrossFrame::new(game.clone(), board, GameState::InProgress).unwrap();
+            recorder.on_frame(&frame, false);
+        }
+        // frames 0, 2 and 4 are recorded
+        assert_eq!(recorder.boards.len(), 3);
+        let board = GameBoard(vec![GameBoardRow(vec![Filled])]);
+        let result = PicrossFrame::new(game.clone(), board, GameState::Complete).unwrap();
+        let gif = recorder.finish(&result).unwrap();
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        // skipping every frame still records the first
+        let mut recorder = GifRecorder::new(GifOptions {
+            frame_skip: usize::MAX,
+            ..Default::default()
+        });
+        for tile in tiles.iter() {
+            let board = GameBoard(vec![GameBoardRow(vec![tile.clone()])]);
+            let frame = PicrossFrame::new(game.clone(), board, GameState::InProgress).unwrap();
+            recorder.on_frame(&frame, false);
+        }
+        assert_eq!(recorder.boards.len(), 1);
+    }
+}
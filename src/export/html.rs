@@ -0,0 +1,229 @@
+use crate::{
+    error::PicrossError,
+    game_board::{GameBoard, TileState},
+    picross::{AxisRules, PicrossGame},
+};
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; display: flex; flex-direction: column; align-items: center; }
+table { border-collapse: collapse; user-select: none; }
+td { width: 24px; height: 24px; padding: 0; text-align: center; font-size: 13px; }
+td.clue { color: #333; }
+td.row-clue { text-align: right; padding-right: 6px; white-space: nowrap; }
+td.column-clue { vertical-align: bottom; line-height: 16px; padding-bottom: 4px; }
+td.cell { border: 1px solid #999; cursor: pointer; }
+td.cell.filled { background: #222; }
+td.cell.marked::after { content: "\00d7"; color: #c33; font-size: 18px; }
+td.cell.guide-right { border-right: 2px solid #222; }
+td.cell.guide-bottom { border-bottom: 2px solid #222; }
+td.cell.wrong { background: #f99; }
+#status { margin-top: 12px; min-height: 1.5em; }
+button { margin: 12px 4px 0; }
+"#;
+
+const SCRIPT: &str = r#"
+const board = rows.map(() => columns.map(() => 0));
+const table = document.getElementById("board");
+const statusLine = document.getElementById("status");
+const maxRowClues = Math.max(...rows.map((rule) => rule.length));
+
+const header = table.insertRow();
+header.insertCell().colSpan = maxRowClues;
+columns.forEach((rule) => {
+  const cell = header.insertCell();
+  cell.className = "clue column-clue";
+  cell.innerHTML = rule.join("<br>");
+});
+
+const cells = rows.map((rule, y) => {
+  const row = table.insertRow();
+  for (let i = 0; i < maxRowClues - rule.length; i++) {
+    row.insertCell();
+  }
+  rule.forEach((chunk) => {
+    const cell = row.insertCell();
+    cell.className = "clue row-clue";
+    cell.textContent = chunk;
+  });
+  return columns.map((_, x) => {
+    const cell = row.insertCell();
+    cell.className = "cell";
+    if ((x + 1) % 5 === 0 && x + 1 < columns.length) cell.classList.add("guide-right");
+    if ((y + 1) % 5 === 0 && y + 1 < rows.length) cell.classList.add("guide-bottom");
+    cell.addEventListener("click", (event) => toggle(x, y, event.shiftKey ? 2 : 1));
+    cell.addEventListener("contextmenu", (event) => {
+      event.preventDefault();
+      toggle(x, y, 2);
+    });
+    return cell;
+  });
+});
+
+function toggle(x, y, state) {
+  board[y][x] = board[y][x] === state ? 0 : state;
+  paint(x, y);
+  statusLine.textContent = "";
+}
+
+function paint(x, y) {
+  const cell = cells[y][x];
+  cell.classList.remove("wrong");
+  cell.classList.toggle("filled", board[y][x] === 1);
+  cell.classList.toggle("marked", board[y][x] === 2);
+}
+
+function check() {
+  let wrong = 0;
+  let missing = 0;
+  solution.forEach((line, y) => {
+    [...line].forEach((expected, x) => {
+      const filled = board[y][x] === 1;
+      if (filled && expected === "0") {
+        wrong++;
+        cells[y][x].classList.add("wrong");
+      } else if (!filled && expected === "1") {
+        missing++;
+      }
+    });
+  });
+  if (wrong > 0) {
+    statusLine.textContent = wrong + " cell(s) are filled incorrectly.";
+  } else if (missing > 0) {
+    statusLine.textContent = "No mistakes so far, " + missing + " cell(s) left to fill.";
+  } else {
+    statusLine.textContent = "Solved!";
+  }
+}
+
+function reset() {
+  rows.forEach((_, y) => columns.forEach((_, x) => {
+    board[y][x] = 0;
+    paint(x, y);
+  }));
+  statusLine.textContent = "";
+}
+
+document.getElementById("check").addEventListener("click", check);
+document.getElementById("reset").addEventListener("click", reset);
+"#;
+
+fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn axis_rules_to_json(rules: &AxisRules) -> String {
+    let rules: Vec<String> = rules
+        .0
+        .iter()
+        .map(|rule| {
+            let chunks: Vec<String> = rule.0.iter().map(|chunk| chunk.to_string()).collect();
+            format!("[{}]", chunks.join(","))
+        })
+        .collect();
+    format!("[{}]", rules.join(","))
+}
+
+fn solution_to_json(solution: &GameBoard) -> String {
+    let rows: Vec<String> = solution
+        .0
+        .iter()
+        .map(|row| {
+            let tiles: String = row
+                .0
+                .iter()
+                .map(|tile| match tile {
+                    TileState::Filled => '1',
+                    _ => '0',
+                })
+                .collect();
+            format!("\"{}\"", tiles)
+        })
+        .collect();
+    format!("[{}]", rows.join(","))
+}
+
+/// renders a single self contained page where the puzzle can be played and
+/// checked against `solution`
+pub fn render_html(
+    game: &PicrossGame,
+    solution: &GameBoard,
+    title: &str,
+) -> Result<String, PicrossError> {
+    if game.width() != solution.width() || game.height() != solution.height() {
+        return Err(PicrossError::DimensionMismatch {
+            expected: (game.width(), game.height()),
+            found: (solution.width(), solution.height()),
+        });
+    }
+    if solution
+        .0
+        .iter()
+        .any(|row| row.0.contains(&TileState::Undetermined))
+    {
+        return Err(PicrossError::Validation(
+            "solution must not contain undetermined tiles".to_string(),
+        ));
+    }
+    let title = escape_html(title);
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>{STYLE}</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>Click to fill a cell, right click or shift click to mark it as empty.</p>
+<table id="board"></table>
+<div>
+<button id="check">Check</button>
+<button id="reset">Reset</button>
+</div>
+<div id="status"></div>
+<script>
+const rows = {rows};
+const columns = {columns};
+const solution = {solution};
+{SCRIPT}</script>
+</body>
+</html>
+"#,
+        rows = axis_rules_to_json(&game.rows),
+        columns = axis_rules_to_json(&game.columns),
+        solution = solution_to_json(solution),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_board::{GameBoardRow, TileState::*};
+
+    #[test]
+    fn test_render_html_embeds_puzzle() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let solution = GameBoard(vec![
+            GameBoardRow(vec![Filled, Empty, Filled]),
+            GameBoardRow(vec![Empty, Filled, Empty]),
+            GameBoardRow(vec![Filled, Empty, Filled]),
+        ]);
+        let html = render_html(&game, &solution, "X <marks> the spot").unwrap();
+        assert!(html.contains("<title>X &lt;marks&gt; the spot</title>"));
+        assert!(html.contains("const rows = [[1,1],[1],[1,1]];"));
+        assert!(html.contains("const columns = [[1,1],[1],[1,1]];"));
+        assert!(html.contains(r#"const solution = ["101","010","101"];"#));
+    }
+
+    #[test]
+    fn test_render_html_rejects_incomplete_solution() {
+        let game = PicrossGame::from_rules("1", "1").unwrap();
+        assert!(render_html(&game, &GameBoard::new(1, 1), "").is_err());
+        assert!(render_html(&game, &GameBoard::new(2, 1), "").is_err());
+    }
+}
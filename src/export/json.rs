@@ -0,0 +1,95 @@
+use std::{fmt::Write, time::Duration};
+
+use crate::{
+    export::escape_json,
+    game_board::TileState,
+    render::{GameState, PicrossFrame},
+};
+
+/// a solve as a JSON object, the grid holds 1 for filled tiles, 0 for empty
+/// ones and null for tiles the solver left undetermined
+pub fn render_json(
+    name: &str,
+    frame: &PicrossFrame,
+    solver: &str,
+    elapsed: Duration,
+    statistics: &[(&str, usize)],
+) -> String {
+    let state = match frame.game_state {
+        GameState::InProgress => "in_progress",
+        GameState::Invalid => "invalid",
+        GameState::Complete => "complete",
+    };
+    let grid = frame
+        .board
+        .0
+        .iter()
+        .map(|row| {
+            let tiles = row
+                .0
+                .iter()
+                .map(|tile| match tile {
+                    TileState::Filled => "1",
+                    TileState::Empty => "0",
+                    TileState::Undetermined => "null",
+                })
+                .collect::<Vec<&str>>()
+                .join(",");
+            format!("[{}]", tiles)
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    let mut statistics_json = String::new();
+    for (index, (key, value)) in statistics.iter().enumerate() {
+        let separator = if index == 0 { "" } else { "," };
+        let _ = write!(
+            statistics_json,
+            "{}\"{}\":{}",
+            separator,
+            escape_json(key),
+            value
+        );
+    }
+    format!(
+        "{{\"puzzle\":\"{}\",\"state\":\"{}\",\"width\":{},\"height\":{},\"solver\":\"{}\",\"elapsed_ms\":{:.3},\"statistics\":{{{}}},\"solution\":[{}]}}",
+        escape_json(name),
+        state,
+        frame.board.width(),
+        frame.board.height(),
+        escape_json(solver),
+        elapsed.as_secs_f64() * 1000.0,
+        statistics_json,
+        grid,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game_board::{GameBoard, GameBoardRow, TileState::*},
+        picross::PicrossGame,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_render_json() {
+        let game = PicrossGame::from_rules("1,0", "1,0").unwrap();
+        let board = GameBoard(vec![
+            GameBoardRow(vec![Filled, Empty]),
+            GameBoardRow(vec![Empty, Undetermined]),
+        ]);
+        let frame = PicrossFrame::new(game, board, GameState::InProgress).unwrap();
+        let json = render_json(
+            "a \"dot\"",
+            &frame,
+            "v4",
+            Duration::from_micros(1500),
+            &[("frames", 2), ("conflicts", 0)],
+        );
+        assert_eq!(
+            json,
+            "{\"puzzle\":\"a \\\"dot\\\"\",\"state\":\"in_progress\",\"width\":2,\"height\":2,\"solver\":\"v4\",\"elapsed_ms\":1.500,\"statistics\":{\"frames\":2,\"conflicts\":0},\"solution\":[[1,0],[0,null]]}"
+        );
+    }
+}
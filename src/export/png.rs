@@ -0,0 +1,313 @@
+use crate::{
+    error::PicrossError,
+    game_board::{GameBoard, TileState},
+    image::LumaImage,
+    picross::{AxisRules, PicrossGame},
+};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const MAX_STORED_BLOCK: usize = 65535;
+
+const FILLED: u8 = 0;
+const EMPTY: u8 = 255;
+const UNDETERMINED: u8 = 200;
+const GRID_LINE: u8 = 160;
+const GUIDE_LINE: u8 = 0;
+
+/// 3x5 digit glyphs, one byte per row with the three low bits as pixels
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// wraps `data` in a zlib stream made of uncompressed deflate blocks
+pub(crate) fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut output = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        output.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let length = block.len() as u16;
+        output.push(is_final as u8);
+        output.extend(length.to_le_bytes());
+        output.extend((!length).to_le_bytes());
+        output.extend(block);
+    }
+    output.extend(adler32(data).to_be_bytes());
+    output
+}
+
+fn write_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    output.extend((data.len() as u32).to_be_bytes());
+    let start = output.len();
+    output.extend(kind);
+    output.extend(data);
+    let crc = crc32(&output[start..]);
+    output.extend(crc.to_be_bytes());
+}
+
+/// encodes an 8 bit grayscale png
+pub(crate) fn encode_png(image: &LumaImage) -> Vec<u8> {
+    let mut header = vec![];
+    header.extend((image.width as u32).to_be_bytes());
+    header.extend((image.height as u32).to_be_bytes());
+    // bit depth 8, grayscale, deflate, adaptive filtering, no interlace
+    header.extend([8, 0, 0, 0, 0]);
+
+    let mut raw = Vec::with_capacity((image.width + 1) * image.height);
+    for row in image.pixels.chunks(image.width.max(1)).take(image.height) {
+        raw.push(0);
+        raw.extend(row);
+    }
+
+    let mut output = SIGNATURE.to_vec();
+    write_chunk(&mut output, b"IHDR", &header);
+    write_chunk(&mut output, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut output, b"IEND", &[]);
+    output
+}
+
+fn tile_value(tile: &TileState) -> u8 {
+    match tile {
+        TileState::Filled => FILLED,
+        TileState::Empty => EMPTY,
+        TileState::Undetermined => UNDETERMINED,
+    }
+}
+
+fn draw_tiles(canvas: &mut LumaImage, board: &GameBoard, left: usize, top: usize, scale: usize) {
+    for (y, row) in board.0.iter().enumerate() {
+        for (x, tile) in row.0.iter().enumerate() {
+            canvas.fill_rect(
+                left + x * scale,
+                top + y * scale,
+                scale,
+                scale,
+                tile_value(tile),
+            );
+        }
+    }
+}
+
+fn draw_number(
+    canvas: &mut LumaImage,
+    number: usize,
+    area: (usize, usize, usize, usize),
+    unit: usize,
+) {
+    let (left, top, width, height) = area;
+    let digits = number.to_string();
+    let text_width = (digits.len() * 4 - 1) * unit;
+    let x = left + width.saturating_sub(text_width) / 2;
+    let y = top + height.saturating_sub(5 * unit) / 2;
+    for (index, digit) in digits.bytes().enumerate() {
+        let glyph = DIGITS[(digit - b'0') as usize];
+        for (glyph_y, bits) in glyph.iter().enumerate() {
+            for glyph_x in 0..3 {
+                if bits & (0b100 >> glyph_x) != 0 {
+                    canvas.fill_rect(
+                        x + (index * 4 + glyph_x) * unit,
+                        y + glyph_y * unit,
+                        unit,
+                        unit,
+                        FILLED,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn max_clue_count(rules: &AxisRules) -> usize {
+    rules.0.iter().map(|rule| rule.0.len()).max().unwrap_or(0)
+}
+
+/// renders only the board, `scale` pixels per tile
+pub fn render_board_png(board: &GameBoard, scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let mut canvas = LumaImage::new(board.width() * scale, board.height() * scale, EMPTY);
+    draw_tiles(&mut canvas, board, 0, 0, scale);
+    encode_png(&canvas)
+}
+
+/// renders the board surrounded by its clues, like `PicrossFrame::render`
+pub fn render_frame_png(
+    game: &PicrossGame,
+    board: &GameBoard,
+    scale: usize,
+) -> Result<Vec<u8>, PicrossError> {
+    if game.width() != board.width() || game.height() != board.height() {
+        return Err(PicrossError::DimensionMismatch {
+            expected: (game.width(), game.height()),
+            found: (board.width(), board.height()),
+        });
+    }
+    let scale = scale.max(1);
+    // digits are 3x5 glyphs, two of them with a gap fill an 8 unit cell
+    let unit = (scale / 8).max(1);
+    let margin = scale / 2;
+    let row_digits = game
+        .rows
+        .0
+        .iter()
+        .flat_map(|rule| rule.0.iter())
+        .map(|chunk| chunk.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let row_slot = scale.max((row_digits * 4 + 1) * unit);
+    let grid_left = margin + max_clue_count(&game.rows) * row_slot;
+    let grid_top = margin + max_clue_count(&game.columns) * scale;
+    let mut canvas = LumaImage::new(
+        grid_left + game.width() * scale + margin + 1,
+        grid_top + game.height() * scale + margin + 1,
+        EMPTY,
+    );
+
+    for (y, rule) in game.rows.0.iter().enumerate() {
+        for (index, chunk) in rule.0.iter().enumerate() {
+            let left = grid_left - (rule.0.len() - index) * row_slot;
+            draw_number(
+                &mut canvas,
+                *chunk,
+                (left, grid_top + y * scale, row_slot, scale),
+                unit,
+            );
+        }
+    }
+    for (x, rule) in game.columns.0.iter().enumerate() {
+        for (index, chunk) in rule.0.iter().enumerate() {
+            let top = grid_top - (rule.0.len() - index) * scale;
+            draw_number(
+                &mut canvas,
+                *chunk,
+                (grid_left + x * scale, top, scale, scale),
+                unit,
+            );
+        }
+    }
+
+    draw_tiles(&mut canvas, board, grid_left, grid_top, scale);
+    let line_value = |index: usize, count: usize| {
+        if index == 0 || index == count || index.is_multiple_of(5) {
+            GUIDE_LINE
+        } else {
+            GRID_LINE
+        }
+    };
+    for x in 0..=game.width() {
+        let value = line_value(x, game.width());
+        canvas.fill_rect(
+            grid_left + x * scale,
+            grid_top,
+            1,
+            game.height() * scale + 1,
+            value,
+        );
+    }
+    for y in 0..=game.height() {
+        let value = line_value(y, game.height());
+        canvas.fill_rect(
+            grid_left,
+            grid_top + y * scale,
+            game.width() * scale + 1,
+            1,
+            value,
+        );
+    }
+    Ok(encode_png(&canvas))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_board::{GameBoardRow, TileState::*};
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_zlib_stored_blocks() {
+        let data = vec![7; MAX_STORED_BLOCK + 10];
+        let stream = zlib_stored(&data);
+        assert_eq!(&stream[..2], &[0x78, 0x01]);
+        assert_eq!(&stream[2..7], &[0, 0xff, 0xff, 0, 0]);
+        let second_block = 7 + MAX_STORED_BLOCK;
+        assert_eq!(
+            &stream[second_block..second_block + 5],
+            &[1, 10, 0, 0xf5, 0xff]
+        );
+        assert_eq!(stream.len(), 2 + 5 + MAX_STORED_BLOCK + 5 + 10 + 4);
+        assert_eq!(&stream[stream.len() - 4..], &adler32(&data).to_be_bytes());
+
+        assert_eq!(
+            zlib_stored(&[]),
+            vec![0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_render_board_png() {
+        let board = GameBoard(vec![
+            GameBoardRow(vec![Filled, Empty, Filled]),
+            GameBoardRow(vec![Empty, Filled, Undetermined]),
+        ]);
+        let png = render_board_png(&board, 2);
+        assert_eq!(&png[..8], &SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..20], &6_u32.to_be_bytes());
+        assert_eq!(&png[20..24], &4_u32.to_be_bytes());
+        assert_eq!(&png[29..33], &crc32(&png[12..29]).to_be_bytes());
+        assert_eq!(&png[37..41], b"IDAT");
+        // the first scanline follows the zlib and stored block headers
+        assert_eq!(&png[48..55], &[0, 0, 0, 255, 255, 0, 0]);
+        assert_eq!(
+            &png[png.len() - 12..],
+            &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn test_render_frame_png() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let board = GameBoard::new(3, 3);
+        let png = render_frame_png(&game, &board, 8).unwrap();
+        // margin 4, two clue slots of 8 pixels, three cells and a closing grid line
+        assert_eq!(&png[16..20], &(4 + 16 + 24 + 4 + 1_u32).to_be_bytes());
+        assert!(render_frame_png(&game, &GameBoard::new(2, 3), 8).is_err());
+    }
+}
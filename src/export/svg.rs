@@ -0,0 +1,194 @@
+use std::fmt::Write;
+
+use crate::{
+    game_board::{GameBoard, TileState},
+    picross::{AxisRules, PicrossGame},
+};
+
+const MARGIN: f64 = 10.0;
+/// average glyph width relative to the font size for sans-serif digits
+const GLYPH_WIDTH: f64 = 0.6;
+
+/// how `render_svg` lays out the sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// width and height of a tile in pixels
+    pub cell_size: f64,
+    /// draw a thicker line after every n cells
+    pub guide_interval: usize,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 20.0,
+            guide_interval: 5,
+        }
+    }
+}
+
+fn max_digits(rules: &AxisRules) -> usize {
+    rules
+        .0
+        .iter()
+        .flat_map(|rule| rule.0.iter())
+        .map(|chunk| chunk.to_string().len())
+        .max()
+        .unwrap_or(1)
+}
+
+fn max_clue_count(rules: &AxisRules) -> usize {
+    rules.0.iter().map(|rule| rule.0.len()).max().unwrap_or(0)
+}
+
+/// renders a printable puzzle sheet, filling in `solution` when provided
+pub fn render_svg(
+    game: &PicrossGame,
+    solution: Option<&GameBoard>,
+    options: &SvgOptions,
+) -> String {
+    let cell = options.cell_size;
+    let font_size = cell * 0.6;
+
+    // row clues sit side by side, so each slot grows to fit the widest number
+    let row_digits = max_digits(&game.rows) as f64;
+    let row_slot = cell.max(row_digits * font_size * GLYPH_WIDTH + cell * 0.3);
+    // column clues share the width of a cell, so long numbers shrink instead
+    let column_digits = max_digits(&game.columns) as f64;
+    let column_font_size = font_size.min((cell - 2.0) / (column_digits * GLYPH_WIDTH));
+
+    let grid_left = MARGIN + max_clue_count(&game.rows) as f64 * row_slot;
+    let grid_top = MARGIN + max_clue_count(&game.columns) as f64 * cell;
+    let grid_width = game.width() as f64 * cell;
+    let grid_height = game.height() as f64 * cell;
+    let width = grid_left + grid_width + MARGIN;
+    let height = grid_top + grid_height + MARGIN;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{width}" height="{height}" fill="white"/>"#
+    );
+
+    let _ = writeln!(
+        svg,
+        r#"<g font-family="sans-serif" font-size="{font_size}" text-anchor="middle" dominant-baseline="central">"#
+    );
+    for (y, rule) in game.rows.0.iter().enumerate() {
+        let center_y = grid_top + (y as f64 + 0.5) * cell;
+        for (index, chunk) in rule.0.iter().enumerate() {
+            let slots_from_grid = (rule.0.len() - index) as f64;
+            let center_x = grid_left - (slots_from_grid - 0.5) * row_slot;
+            let _ = writeln!(svg, r#"<text x="{center_x}" y="{center_y}">{chunk}</text>"#);
+        }
+    }
+    for (x, rule) in game.columns.0.iter().enumerate() {
+        let center_x = grid_left + (x as f64 + 0.5) * cell;
+        for (index, chunk) in rule.0.iter().enumerate() {
+            let slots_from_grid = (rule.0.len() - index) as f64;
+            let center_y = grid_top - (slots_from_grid - 0.5) * cell;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{center_x}" y="{center_y}" font-size="{column_font_size}">{chunk}</text>"#
+            );
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    if let Some(board) = solution {
+        let _ = writeln!(svg, r#"<g fill="black">"#);
+        for (y, row) in board.0.iter().enumerate() {
+            for (x, tile) in row.0.iter().enumerate() {
+                if *tile == TileState::Filled {
+                    let _ = writeln!(
+                        svg,
+                        r#"<rect x="{}" y="{}" width="{cell}" height="{cell}"/>"#,
+                        grid_left + x as f64 * cell,
+                        grid_top + y as f64 * cell
+                    );
+                }
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    let _ = writeln!(svg, r#"<g stroke="black" stroke-linecap="square">"#);
+    let stroke_width = |index: usize, count: usize| {
+        let is_guide = options.guide_interval > 0 && index.is_multiple_of(options.guide_interval);
+        if index == 0 || index == count || is_guide {
+            2.0
+        } else {
+            0.5
+        }
+    };
+    for x in 0..=game.width() {
+        let line_x = grid_left + x as f64 * cell;
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{line_x}" y1="{grid_top}" x2="{line_x}" y2="{}" stroke-width="{}"/>"#,
+            grid_top + grid_height,
+            stroke_width(x, game.width())
+        );
+    }
+    for y in 0..=game.height() {
+        let line_y = grid_top + y as f64 * cell;
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{grid_left}" y1="{line_y}" x2="{}" y2="{line_y}" stroke-width="{}"/>"#,
+            grid_left + grid_width,
+            stroke_width(y, game.height())
+        );
+    }
+    let _ = writeln!(svg, "</g>");
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_board::{GameBoardRow, TileState::*};
+
+    #[test]
+    fn test_render_svg_clues_and_grid() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let svg = render_svg(&game, None, &SvgOptions::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<text").count(), 10);
+        assert_eq!(svg.matches("<line").count(), 8);
+        assert_eq!(svg.matches(r#"stroke-width="2""#).count(), 4);
+        assert!(!svg.contains(r#"<g fill="black">"#));
+    }
+
+    #[test]
+    fn test_render_svg_solution() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let board = GameBoard(vec![
+            GameBoardRow(vec![Filled, Empty, Filled]),
+            GameBoardRow(vec![Empty, Filled, Empty]),
+            GameBoardRow(vec![Filled, Empty, Filled]),
+        ]);
+        let svg = render_svg(&game, Some(&board), &SvgOptions::default());
+        assert_eq!(svg.matches(r#"width="20" height="20""#).count(), 5);
+    }
+
+    #[test]
+    fn test_render_svg_guide_lines() {
+        let game = PicrossGame::from_rules("0,0,0,0,0,0,0,0,0,0", "0,0,0,0,0,0,0,0,0,0").unwrap();
+        let svg = render_svg(&game, None, &SvgOptions::default());
+        // borders plus the guide after the fifth cell on both axes
+        assert_eq!(svg.matches(r#"stroke-width="2""#).count(), 6);
+    }
+
+    #[test]
+    fn test_render_svg_multi_digit_clues() {
+        let game = PicrossGame::from_rules("12", "1,1,1,1,1,1,1,1,1,1,1,1").unwrap();
+        let svg = render_svg(&game, None, &SvgOptions::default());
+        assert!(svg.contains(">12</text>"));
+    }
+}
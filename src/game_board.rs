@@ -1,28 +1,34 @@
-#[allow(dead_code)]
+use std::ops::{Index, IndexMut};
+
+use crate::error::PicrossError;
+
+/// what is known about a single tile
 #[derive(Clone, Debug, PartialEq)]
 pub enum TileState {
+    /// not worked out or not played yet
     Undetermined,
+    /// part of the picture
     Filled,
+    /// known to stay blank
     Empty,
 }
 
+/// a run of `length` filled tiles starting at `index`
 #[derive(Debug, Clone)]
-pub struct Segment {
+pub(crate) struct Segment {
     pub length: usize,
     pub index: usize,
 }
 
+/// the tiles of one line, left to right or top to bottom
 #[derive(Debug, PartialEq, Clone)]
-pub struct GameBoardRow(pub Vec<TileState>);
+pub(crate) struct GameBoardRow(pub(crate) Vec<TileState>);
 
 impl GameBoardRow {
-    pub fn new(width: usize) -> Self {
+    pub(crate) fn new(width: usize) -> Self {
         Self(vec![TileState::Undetermined; width])
     }
-    fn set_tile(&mut self, index: usize, tile: TileState) {
-        self.0[index] = tile;
-    }
-    pub fn build_from_segments(segments: Vec<Segment>, width: usize) -> Result<Self, &'static str> {
+    pub(crate) fn build_from_segments(segments: Vec<Segment>, width: usize) -> Self {
         let row: Vec<TileState> = vec![(); width]
             .iter()
             .enumerate()
@@ -37,15 +43,16 @@ impl GameBoardRow {
                 }
             })
             .collect();
-        Ok(Self(row))
+        Self(row)
     }
 }
 
+/// the tiles of a puzzle, `board[(x, y)]` is the tile in column `x` of row `y`
 #[derive(Debug, PartialEq, Clone)]
-pub struct GameBoard(pub Vec<GameBoardRow>);
+pub struct GameBoard(pub(crate) Vec<GameBoardRow>);
 
-#[allow(dead_code)]
 impl GameBoard {
+    /// a board of undetermined tiles
     pub fn new(width: usize, height: usize) -> Self {
         let board: Vec<GameBoardRow> = vec![(); height]
             .iter()
@@ -53,25 +60,44 @@ impl GameBoard {
             .collect();
         Self(board)
     }
+    /// a board from its rows of tiles, top to bottom; rows of different
+    /// lengths are kept as they are
+    pub fn from_rows(rows: Vec<Vec<TileState>>) -> Self {
+        Self(rows.into_iter().map(GameBoardRow).collect())
+    }
+    /// the tiles of each row, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[TileState]> {
+        self.0.iter().map(|row| &row.0[..])
+    }
+    /// the number of rows
     pub fn height(&self) -> usize {
         self.0.len()
     }
+    /// the length of the first row, 0 for a board without rows
     pub fn width(&self) -> usize {
-        let row_res = self.0.get(0);
+        let row_res = self.0.first();
         match row_res {
             Some(row) => row.0.len(),
             None => 0,
         }
     }
 
-    pub fn merge_board(&self, board: Self) -> Result<Self, &'static str> {
-        let merged_board: Result<Vec<GameBoardRow>, &'static str> = self
+    /// combines what two boards of the same size know, failing with a
+    /// `Contradiction` where one has a tile filled and the other empty
+    pub fn merge_board(&self, board: Self) -> Result<Self, PicrossError> {
+        if self.width() != board.width() || self.height() != board.height() {
+            return Err(PicrossError::DimensionMismatch {
+                expected: (self.width(), self.height()),
+                found: (board.width(), board.height()),
+            });
+        }
+        let merged_board: Result<Vec<GameBoardRow>, PicrossError> = self
             .0
             .clone()
             .into_iter()
             .zip(board.0)
             .map(|(row_a, row_b)| {
-                let merged_row: Result<Vec<TileState>, &'static str> = row_a
+                let merged_row: Result<Vec<TileState>, PicrossError> = row_a
                     .0
                     .into_iter()
                     .zip(row_b.0)
@@ -85,8 +111,10 @@ impl GameBoard {
                         (TileState::Undetermined, TileState::Undetermined) => {
                             Ok(TileState::Undetermined)
                         }
-                        (TileState::Filled, TileState::Empty) => Err("Invalid Tile Combination"),
-                        (TileState::Empty, TileState::Filled) => Err("Invalid Tile Combination"),
+                        (TileState::Filled, TileState::Empty)
+                        | (TileState::Empty, TileState::Filled) => Err(
+                            PicrossError::Contradiction("Invalid Tile Combination".to_string()),
+                        ),
                     })
                     .collect();
                 merged_row.map(GameBoardRow)
@@ -94,51 +122,110 @@ impl GameBoard {
             .collect();
         merged_board.map(GameBoard)
     }
-    pub fn get_column(&self, column_index: usize) -> Vec<TileState> {
+    /// the tiles of column `column_index`, top to bottom
+    pub fn get_column(&self, column_index: usize) -> Result<Vec<TileState>, PicrossError> {
         self.0
             .iter()
-            .map(|row| row.0[column_index].clone())
+            .map(|row| {
+                row.0
+                    .get(column_index)
+                    .cloned()
+                    .ok_or_else(column_out_of_bounds)
+            })
             .collect()
     }
 
-    pub fn set_tile(&mut self, x: usize, y: usize, state: TileState) -> Result<(), &'static str> {
-        let row = self.0.get_mut(y).unwrap();
-        row.set_tile(x, state);
+    /// sets the tile at `(x, y)`, failing outside the board
+    pub fn set_tile(&mut self, x: usize, y: usize, state: TileState) -> Result<(), PicrossError> {
+        let (width, height) = (self.width(), self.height());
+        let tile = self
+            .0
+            .get_mut(y)
+            .and_then(|row| row.0.get_mut(x))
+            .ok_or_else(|| {
+                PicrossError::Validation(format!(
+                    "tile ({}, {}) is outside the {}x{} board",
+                    x, y, width, height
+                ))
+            })?;
+        *tile = state;
         Ok(())
     }
-    pub fn get_column_chunks(&self, index: usize) -> Result<Vec<usize>, &'static str> {
-        let mut chunks: Vec<usize> = vec![];
-        let mut is_collecting = false;
-        let mut count = 0;
-        for row in self.0.iter() {
-            let state = row.0.get(index).unwrap();
-            match state {
-                TileState::Filled => {
-                    count += 1;
-                    is_collecting = true;
-                }
-                TileState::Empty => {
-                    if is_collecting {
-                        chunks.push(count);
-                        count = 0;
-                        is_collecting = false;
-                    }
+    /// the lengths of the filled runs in a column, as a clue lists them;
+    /// fails on undetermined tiles
+    pub fn get_column_chunks(&self, index: usize) -> Result<Vec<usize>, PicrossError> {
+        let column: Vec<&TileState> = self
+            .0
+            .iter()
+            .map(|row| row.0.get(index).ok_or_else(column_out_of_bounds))
+            .collect::<Result<_, _>>()?;
+        get_chunks(column.into_iter())
+    }
+    /// the lengths of the filled runs in a row, as a clue lists them;
+    /// fails on undetermined tiles
+    pub fn get_row_chunks(&self, index: usize) -> Result<Vec<usize>, PicrossError> {
+        let row = self
+            .0
+            .get(index)
+            .ok_or_else(|| PicrossError::Validation("row index out of bounds".to_string()))?;
+        get_chunks(row.0.iter())
+    }
+}
+
+/// the tile at `(x, y)`, panicking outside the board like a slice would
+impl Index<(usize, usize)> for GameBoard {
+    type Output = TileState;
+
+    fn index(&self, (x, y): (usize, usize)) -> &TileState {
+        &self.0[y].0[x]
+    }
+}
+
+impl IndexMut<(usize, usize)> for GameBoard {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut TileState {
+        &mut self.0[y].0[x]
+    }
+}
+
+fn column_out_of_bounds() -> PicrossError {
+    PicrossError::Validation("column index out of bounds".to_string())
+}
+
+fn get_chunks<'a>(tiles: impl Iterator<Item = &'a TileState>) -> Result<Vec<usize>, PicrossError> {
+    let mut chunks: Vec<usize> = vec![];
+    let mut is_collecting = false;
+    let mut count = 0;
+    for state in tiles {
+        match state {
+            TileState::Filled => {
+                count += 1;
+                is_collecting = true;
+            }
+            TileState::Empty => {
+                if is_collecting {
+                    chunks.push(count);
+                    count = 0;
+                    is_collecting = false;
                 }
-                TileState::Undetermined => return Err("Cannot process undetermined tiles"), // Undetermined not
+            }
+            TileState::Undetermined => {
+                return Err(PicrossError::Validation(
+                    "Cannot process undetermined tiles".to_string(),
+                ))
             }
         }
-        if count > 0 {
-            chunks.push(count);
-        }
-        if chunks.is_empty() {
-            chunks = vec![0]
-        }
-        Ok(chunks)
     }
+    if count > 0 {
+        chunks.push(count);
+    }
+    if chunks.is_empty() {
+        chunks = vec![0]
+    }
+    Ok(chunks)
 }
 
-#[allow(dead_code)]
 impl GameBoard {
+    /// two characters per tile and a line per row, "??" for undetermined
     pub fn render(&self) -> String {
         let display_string = self
             .0
@@ -157,6 +244,49 @@ impl GameBoard {
             .join("\n");
         display_string
     }
+    /// a character per tile and a line per two rows, using half blocks for
+    /// the filled tiles and a shade where no tile is filled but one is still
+    /// undetermined
+    pub fn render_compact(&self) -> String {
+        self.0
+            .chunks(2)
+            .map(|rows| {
+                let bottom = rows.get(1).map(|row| &row.0[..]).unwrap_or_default();
+                rows[0]
+                    .0
+                    .iter()
+                    .enumerate()
+                    .map(|(x, top)| match (top, bottom.get(x)) {
+                        (TileState::Filled, Some(TileState::Filled)) => '█',
+                        (TileState::Filled, _) => '▀',
+                        (_, Some(TileState::Filled)) => '▄',
+                        (TileState::Undetermined, _) | (_, Some(TileState::Undetermined)) => '░',
+                        _ => ' ',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+    /// the board in the text image format `PicrossGame::from_text_render`
+    /// reads, "x" for filled tiles and a space for anything else
+    pub fn to_text_image(&self) -> String {
+        self.0
+            .iter()
+            .map(|row| {
+                row.0
+                    .iter()
+                    .map(|tile| match tile {
+                        TileState::Filled => 'x',
+                        _ => ' ',
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
 }
 
 #[cfg(test)]
@@ -173,6 +303,25 @@ mod tests {
         assert_eq!(board_string, expected);
     }
 
+    #[test]
+    fn test_render_compact() {
+        let board = GameBoard(vec![
+            GameBoardRow(vec![Filled, Empty, Filled, Undetermined]),
+            GameBoardRow(vec![Filled, Filled, Empty, Empty]),
+            GameBoardRow(vec![Empty, Undetermined, Filled, Empty]),
+        ]);
+        assert_eq!(board.render_compact(), "█▄▀░\n ░▀ ");
+    }
+
+    #[test]
+    fn test_to_text_image() {
+        let board = GameBoard(vec![
+            GameBoardRow(vec![Filled, Empty, Filled]),
+            GameBoardRow(vec![Empty, Filled, Empty]),
+        ]);
+        assert_eq!(board.to_text_image(), "x x\n x");
+    }
+
     #[test]
     fn test_set_tiles() {
         let mut board = GameBoard::new(3, 3);
@@ -189,12 +338,22 @@ mod tests {
         let _ = board.set_tile(2, 2, Filled);
 
         assert_eq!(board.render(), "██  ██\n  ██  \n██  ██");
+
+        assert!(matches!(
+            board.set_tile(3, 0, Filled),
+            Err(PicrossError::Validation(_))
+        ));
+        assert!(board.get_column(3).is_err());
+        assert!(matches!(
+            board.merge_board(GameBoard::new(2, 3)),
+            Err(PicrossError::DimensionMismatch { .. })
+        ));
     }
 
     #[test]
     fn test_build_row_from_empty_segments() {
         let segments: Vec<Segment> = vec![];
-        let row = GameBoardRow::build_from_segments(segments, 5).unwrap();
+        let row = GameBoardRow::build_from_segments(segments, 5);
         assert_eq!(row, GameBoardRow(vec![Empty, Empty, Empty, Empty, Empty,]))
     }
 
@@ -210,7 +369,7 @@ mod tests {
                 length: 1,
             },
         ];
-        let row = GameBoardRow::build_from_segments(segments, 5).unwrap();
+        let row = GameBoardRow::build_from_segments(segments, 5);
         assert_eq!(
             row,
             GameBoardRow(vec![Filled, Filled, Filled, Empty, Filled,])
@@ -227,5 +386,7 @@ mod tests {
         assert_eq!(board.get_column_chunks(0).unwrap(), vec![1, 1]);
         assert_eq!(board.get_column_chunks(1).unwrap(), vec![1]);
         assert_eq!(board.get_column_chunks(2).unwrap(), vec![2]);
+        assert_eq!(board.get_row_chunks(1).unwrap(), vec![1]);
+        assert_eq!(board.get_row_chunks(2).unwrap(), vec![3]);
     }
 }
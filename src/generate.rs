@@ -0,0 +1,154 @@
+use crate::{
+    analysis::find_solutions,
+    error::PicrossError,
+    game_board::{GameBoard, GameBoardRow, TileState},
+    picross::PicrossGame,
+};
+
+/// the size and shape of the puzzles `generate` draws
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorOptions {
+    /// tiles per row
+    pub width: usize,
+    /// tiles per column
+    pub height: usize,
+    /// the chance of each tile being filled, from 0 to 1
+    pub density: f64,
+    /// the same seed and options always give the same puzzle
+    pub seed: u64,
+    /// keep drawing boards until one has a single solution
+    pub unique: bool,
+    /// boards drawn before giving up on a unique puzzle
+    pub max_attempts: usize,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            width: 10,
+            height: 10,
+            density: 0.55,
+            seed: 1,
+            unique: false,
+            max_attempts: 100,
+        }
+    }
+}
+
+/// xorshift64*, plenty for picking tiles and reproducible from a seed
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        // one splitmix64 step spreads nearby seeds apart, and the state must
+        // never be zero
+        let mut state = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self((state ^ (state >> 31)).max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn random_board(options: &GeneratorOptions, random: &mut Random) -> GameBoard {
+    GameBoard(
+        (0..options.height)
+            .map(|_| {
+                GameBoardRow(
+                    (0..options.width)
+                        .map(|_| match random.next_f64() < options.density {
+                            true => TileState::Filled,
+                            false => TileState::Empty,
+                        })
+                        .collect(),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// a random puzzle, the same options always give the same puzzle
+pub fn generate(options: &GeneratorOptions) -> Result<PicrossGame, PicrossError> {
+    if options.width == 0 || options.height == 0 {
+        return Err(PicrossError::Validation(
+            "puzzles need at least one row and one column".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&options.density) {
+        return Err(PicrossError::Validation(format!(
+            "density {} is not between 0 and 1",
+            options.density
+        )));
+    }
+    let mut random = Random::new(options.seed);
+    for _ in 0..options.max_attempts.max(1) {
+        let game = PicrossGame::from_board(&random_board(options, &mut random))?;
+        if !options.unique || find_solutions(&game, 2).len() == 1 {
+            return Ok(game);
+        }
+    }
+    Err(PicrossError::Validation(format!(
+        "no puzzle with a unique solution found in {} attempts",
+        options.max_attempts
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let options = GeneratorOptions {
+            width: 8,
+            height: 6,
+            seed: 42,
+            ..Default::default()
+        };
+        let game = generate(&options).unwrap();
+        assert_eq!((game.width(), game.height()), (8, 6));
+        assert_eq!(generate(&options).unwrap(), game);
+        let other_seed = GeneratorOptions {
+            seed: 43,
+            ..options
+        };
+        assert_ne!(generate(&other_seed).unwrap(), game);
+
+        let full = GeneratorOptions {
+            density: 1.0,
+            ..GeneratorOptions::default()
+        };
+        assert_eq!(
+            generate(&full).unwrap().rows.0[0],
+            crate::picross::LineRule(vec![10])
+        );
+        let invalid = GeneratorOptions {
+            density: 1.5,
+            ..GeneratorOptions::default()
+        };
+        assert!(generate(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_generate_unique() {
+        let options = GeneratorOptions {
+            width: 5,
+            height: 5,
+            unique: true,
+            ..Default::default()
+        };
+        let game = generate(&options).unwrap();
+        assert_eq!(find_solutions(&game, 2).len(), 1);
+    }
+}
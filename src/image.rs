@@ -0,0 +1,238 @@
+use std::str::FromStr;
+
+use crate::{
+    error::PicrossError,
+    game_board::{GameBoard, GameBoardRow, TileState},
+    picross::PicrossGame,
+};
+
+pub(crate) mod bmp;
+pub(crate) mod netpbm;
+
+/// how an image is scaled down to the grid size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScaleMode {
+    /// each tile takes the pixel nearest its center
+    Nearest,
+    /// each tile averages every pixel it covers
+    Box,
+}
+
+impl FromStr for ScaleMode {
+    type Err = PicrossError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nearest" => Ok(ScaleMode::Nearest),
+            "box" => Ok(ScaleMode::Box),
+            _ => Err(PicrossError::Validation(format!(
+                "invalid scale mode \"{}\"\n Available modes are: nearest, box",
+                s
+            ))),
+        }
+    }
+}
+
+/// how an image becomes a puzzle
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageOptions {
+    /// pixels with a luminance below the threshold become filled tiles
+    pub threshold: u8,
+    /// target grid size as (width, height)
+    pub resize: Option<(usize, usize)>,
+    /// how to scale when `resize` is set
+    pub scale_mode: ScaleMode,
+    /// paletted bmp pixels using this index become filled tiles
+    pub palette_index: Option<u8>,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 128,
+            resize: None,
+            scale_mode: ScaleMode::Nearest,
+            palette_index: None,
+        }
+    }
+}
+
+/// 8 bit luminance image, 0 is black and 255 is white
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LumaImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+pub(crate) fn luminance(red: u8, green: u8, blue: u8) -> u8 {
+    ((299 * red as u32 + 587 * green as u32 + 114 * blue as u32) / 1000) as u8
+}
+
+impl LumaImage {
+    pub(crate) fn new(width: usize, height: usize, fill: u8) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    /// fills a rectangle, clipping anything outside the image
+    pub(crate) fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, value: u8) {
+        for row in y..(y + height).min(self.height) {
+            for column in x..(x + width).min(self.width) {
+                self.pixels[row * self.width + column] = value;
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.width + x]
+    }
+
+    pub(crate) fn resize(&self, width: usize, height: usize, mode: ScaleMode) -> Self {
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = match mode {
+                    ScaleMode::Nearest => {
+                        let source_x = (x * self.width + self.width / 2) / width;
+                        let source_y = (y * self.height + self.height / 2) / height;
+                        self.get(source_x.min(self.width - 1), source_y.min(self.height - 1))
+                    }
+                    ScaleMode::Box => {
+                        let x_start = x * self.width / width;
+                        let x_end = ((x + 1) * self.width / width).max(x_start + 1);
+                        let y_start = y * self.height / height;
+                        let y_end = ((y + 1) * self.height / height).max(y_start + 1);
+                        let mut sum = 0;
+                        for source_y in y_start..y_end {
+                            for source_x in x_start..x_end {
+                                sum += self.get(source_x, source_y) as usize;
+                            }
+                        }
+                        (sum / ((x_end - x_start) * (y_end - y_start))) as u8
+                    }
+                };
+                pixels.push(pixel);
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub(crate) fn to_board(&self, threshold: u8) -> GameBoard {
+        let rows = self
+            .pixels
+            .chunks(self.width.max(1))
+            .take(self.height)
+            .map(|row| {
+                let tiles = row
+                    .iter()
+                    .map(|pixel| {
+                        if *pixel < threshold {
+                            TileState::Filled
+                        } else {
+                            TileState::Empty
+                        }
+                    })
+                    .collect();
+                GameBoardRow(tiles)
+            })
+            .collect();
+        GameBoard(rows)
+    }
+}
+
+/// parses a "WIDTHxHEIGHT" string such as "20x15"
+pub fn parse_dimensions(input: &str) -> Result<(usize, usize), PicrossError> {
+    let error = || {
+        PicrossError::Validation(format!(
+            "invalid dimensions \"{}\", expected WIDTHxHEIGHT",
+            input
+        ))
+    };
+    let (width, height) = input.split_once(['x', 'X']).ok_or_else(error)?;
+    let width: usize = width.trim().parse().map_err(|_| error())?;
+    let height: usize = height.trim().parse().map_err(|_| error())?;
+    if width == 0 || height == 0 {
+        return Err(error());
+    }
+    Ok((width, height))
+}
+
+pub(crate) fn game_from_image(
+    image: &LumaImage,
+    options: &ImageOptions,
+) -> Result<(PicrossGame, GameBoard), PicrossError> {
+    if image.width == 0 || image.height == 0 {
+        return Err(PicrossError::Validation("image has no pixels".to_string()));
+    }
+    let board = match options.resize {
+        Some((width, height)) => image
+            .resize(width, height, options.scale_mode)
+            .to_board(options.threshold),
+        None => image.to_board(options.threshold),
+    };
+    let game = PicrossGame::from_board(&board)?;
+    Ok((game, board))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_board::TileState::*;
+
+    fn checkerboard() -> LumaImage {
+        LumaImage {
+            width: 4,
+            height: 4,
+            pixels: vec![
+                0, 0, 255, 255, //
+                0, 0, 255, 255, //
+                255, 255, 0, 0, //
+                255, 255, 0, 100,
+            ],
+        }
+    }
+
+    #[test]
+    fn test_resize_nearest() {
+        let resized = checkerboard().resize(2, 2, ScaleMode::Nearest);
+        assert_eq!(resized.pixels, vec![0, 255, 255, 100]);
+    }
+
+    #[test]
+    fn test_resize_box() {
+        let resized = checkerboard().resize(2, 2, ScaleMode::Box);
+        assert_eq!(resized.pixels, vec![0, 255, 255, 25]);
+    }
+
+    #[test]
+    fn test_game_from_image() {
+        let options = ImageOptions {
+            resize: Some((2, 2)),
+            ..Default::default()
+        };
+        let (game, board) = game_from_image(&checkerboard(), &options).unwrap();
+        assert_eq!(
+            board,
+            GameBoard(vec![
+                GameBoardRow(vec![Filled, Empty]),
+                GameBoardRow(vec![Empty, Filled]),
+            ])
+        );
+        assert_eq!(game, PicrossGame::from_rules("1,1", "1,1").unwrap());
+    }
+
+    #[test]
+    fn test_parse_dimensions() {
+        assert_eq!(parse_dimensions("20x15").unwrap(), (20, 15));
+        assert!(parse_dimensions("20").is_err());
+        assert!(parse_dimensions("0x5").is_err());
+    }
+}
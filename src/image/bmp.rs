@@ -0,0 +1,256 @@
+use super::{luminance, LumaImage};
+use crate::error::PicrossError;
+
+const FILE_HEADER_SIZE: usize = 14;
+const CORE_HEADER_SIZE: usize = 12;
+const COMPRESSION_RGB: u32 = 0;
+const COMPRESSION_BITFIELDS: u32 = 3;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| "unexpected end of bmp header".to_string())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or_else(|| "unexpected end of bmp header".to_string())
+}
+
+struct BmpHeader {
+    pixel_offset: usize,
+    header_size: usize,
+    width: usize,
+    height: usize,
+    top_down: bool,
+    bits_per_pixel: u16,
+    colors_used: usize,
+}
+
+fn read_header(data: &[u8]) -> Result<BmpHeader, String> {
+    if data.get(0..2) != Some(b"BM") {
+        return Err("not a bmp file".to_string());
+    }
+    let pixel_offset = read_u32(data, 10)? as usize;
+    let header_size = read_u32(data, FILE_HEADER_SIZE)? as usize;
+    if header_size == CORE_HEADER_SIZE {
+        return Ok(BmpHeader {
+            pixel_offset,
+            header_size,
+            width: read_u16(data, 18)? as usize,
+            height: read_u16(data, 20)? as usize,
+            top_down: false,
+            bits_per_pixel: read_u16(data, 24)?,
+            colors_used: 0,
+        });
+    }
+    if header_size < 40 {
+        return Err(format!("unsupported bmp header size {}", header_size));
+    }
+    let width = read_u32(data, 18)? as i32;
+    let height = read_u32(data, 22)? as i32;
+    let bits_per_pixel = read_u16(data, 28)?;
+    let compression = read_u32(data, 30)?;
+    let is_supported = compression == COMPRESSION_RGB
+        || (compression == COMPRESSION_BITFIELDS && bits_per_pixel == 32);
+    if !is_supported {
+        return Err("compressed bmp files are not supported".to_string());
+    }
+    if width < 0 {
+        return Err("bmp width must be positive".to_string());
+    }
+    Ok(BmpHeader {
+        pixel_offset,
+        header_size,
+        width: width as usize,
+        height: height.unsigned_abs() as usize,
+        top_down: height < 0,
+        bits_per_pixel,
+        colors_used: read_u32(data, 46)? as usize,
+    })
+}
+
+fn read_palette(data: &[u8], header: &BmpHeader) -> Result<Vec<u8>, String> {
+    let entry_size = if header.header_size == CORE_HEADER_SIZE {
+        3
+    } else {
+        4
+    };
+    let count = if header.colors_used == 0 {
+        1 << header.bits_per_pixel
+    } else {
+        header.colors_used
+    };
+    let start = FILE_HEADER_SIZE + header.header_size;
+    let palette = count
+        .checked_mul(entry_size)
+        .and_then(|size| data.get(start..start.checked_add(size)?))
+        .ok_or("unexpected end of bmp palette")?;
+    Ok(palette
+        .chunks(entry_size)
+        .map(|bgr| luminance(bgr[2], bgr[1], bgr[0]))
+        .collect())
+}
+
+/// decodes an uncompressed 1, 4, 8, 24 or 32 bit bmp into a luminance image.
+/// When `filled_index` is set, paletted pixels using that index become black
+/// and every other pixel becomes white.
+pub(crate) fn decode(data: &[u8], filled_index: Option<u8>) -> Result<LumaImage, PicrossError> {
+    read_image(data, filled_index).map_err(PicrossError::Format)
+}
+
+fn read_image(data: &[u8], filled_index: Option<u8>) -> Result<LumaImage, String> {
+    let header = read_header(data)?;
+    let bits_per_pixel = header.bits_per_pixel as usize;
+    if !matches!(bits_per_pixel, 1 | 4 | 8 | 24 | 32) {
+        return Err(format!("unsupported bmp bit depth {}", bits_per_pixel));
+    }
+    let is_paletted = bits_per_pixel <= 8;
+    if filled_index.is_some() && !is_paletted {
+        return Err("palette index selection requires a 1, 4 or 8 bit bmp".to_string());
+    }
+    let palette = if is_paletted {
+        read_palette(data, &header)?
+    } else {
+        vec![]
+    };
+    let too_big = || format!("bmp size {}x{} is too big", header.width, header.height);
+    let row_size = bits_per_pixel
+        .checked_mul(header.width)
+        .ok_or_else(too_big)?
+        .div_ceil(32)
+        * 4;
+    let pixel_end = row_size
+        .checked_mul(header.height)
+        .and_then(|size| size.checked_add(header.pixel_offset))
+        .ok_or_else(too_big)?;
+    let pixel_data = data
+        .get(header.pixel_offset..pixel_end)
+        .ok_or("unexpected end of bmp pixel data")?;
+
+    let pixel_count = header
+        .width
+        .checked_mul(header.height)
+        .ok_or_else(too_big)?;
+    let mut pixels = vec![0; pixel_count];
+    for (row_index, row) in pixel_data.chunks(row_size.max(1)).enumerate() {
+        let y = if header.top_down {
+            row_index
+        } else {
+            header.height - 1 - row_index
+        };
+        for x in 0..header.width {
+            let pixel = if is_paletted {
+                let bit_offset = x * bits_per_pixel;
+                let byte = row[bit_offset / 8];
+                let shift = 8 - bits_per_pixel - bit_offset % 8;
+                let index = (byte >> shift) & ((1u16 << bits_per_pixel) - 1) as u8;
+                match filled_index {
+                    Some(filled_index) if filled_index == index => 0,
+                    Some(_) => 255,
+                    None => *palette
+                        .get(index as usize)
+                        .ok_or_else(|| format!("palette index {} out of range", index))?,
+                }
+            } else {
+                let offset = x * bits_per_pixel / 8;
+                luminance(row[offset + 2], row[offset + 1], row[offset])
+            };
+            pixels[y * header.width + x] = pixel;
+        }
+    }
+    Ok(LumaImage {
+        width: header.width,
+        height: header.height,
+        pixels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_bmp(
+        width: i32,
+        height: i32,
+        bits_per_pixel: u16,
+        palette: &[[u8; 4]],
+        rows: &[&[u8]],
+    ) -> Vec<u8> {
+        let pixel_offset = 14 + 40 + palette.len() * 4;
+        let mut data = b"BM".to_vec();
+        data.extend(0u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend((pixel_offset as u32).to_le_bytes());
+        data.extend(40u32.to_le_bytes());
+        data.extend(width.to_le_bytes());
+        data.extend(height.to_le_bytes());
+        data.extend(1u16.to_le_bytes());
+        data.extend(bits_per_pixel.to_le_bytes());
+        data.extend([0; 16]);
+        data.extend((palette.len() as u32).to_le_bytes());
+        data.extend([0; 4]);
+        for entry in palette {
+            data.extend(entry);
+        }
+        for row in rows {
+            data.extend(*row);
+        }
+        data
+    }
+
+    #[test]
+    fn test_decode_1_bit() {
+        let palette = [[0, 0, 0, 0], [255, 255, 255, 0]];
+        let data = build_bmp(
+            3,
+            2,
+            1,
+            &palette,
+            &[&[0b1010_0000, 0, 0, 0], &[0b0100_0000, 0, 0, 0]],
+        );
+        let image = decode(&data, None).unwrap();
+        assert_eq!(image.width, 3);
+        assert_eq!(image.height, 2);
+        assert_eq!(image.pixels, vec![0, 255, 0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_decode_4_bit_palette_index() {
+        let palette = [[0, 0, 0, 0], [0, 0, 255, 0], [255, 0, 0, 0]];
+        let data = build_bmp(3, 1, 4, &palette, &[&[0x12, 0x00, 0, 0]]);
+        let image = decode(&data, Some(2)).unwrap();
+        assert_eq!(image.pixels, vec![255, 0, 255]);
+        let image = decode(&data, None).unwrap();
+        assert_eq!(image.pixels, vec![76, 29, 0]);
+    }
+
+    #[test]
+    fn test_decode_8_bit_top_down() {
+        let palette = [[255, 255, 255, 0], [0, 0, 0, 0]];
+        let data = build_bmp(2, -2, 8, &palette, &[&[1, 0, 0, 0], &[0, 1, 0, 0]]);
+        let image = decode(&data, None).unwrap();
+        assert_eq!(image.pixels, vec![0, 255, 255, 0]);
+    }
+
+    #[test]
+    fn test_decode_true_color() {
+        let data = build_bmp(2, 1, 24, &[], &[&[0, 0, 0, 255, 255, 255, 0, 0]]);
+        assert_eq!(decode(&data, None).unwrap().pixels, vec![0, 255]);
+
+        let data = build_bmp(1, 1, 32, &[], &[&[255, 0, 0, 255]]);
+        assert_eq!(decode(&data, None).unwrap().pixels, vec![29]);
+        assert!(decode(&data, Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(b"P1 1 1 0", None).is_err());
+        let data = build_bmp(4, 4, 8, &[[0, 0, 0, 0]], &[&[0, 0, 0, 0]]);
+        assert!(decode(&data, None).is_err());
+        // checked before allocating the pixels
+        let data = build_bmp(i32::MAX, i32::MIN + 1, 32, &[], &[]);
+        assert!(decode(&data, None).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use super::{luminance, LumaImage};
+use crate::error::PicrossError;
+
+struct NetpbmReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> NetpbmReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(byte) = self.data.get(self.position) {
+            match byte {
+                b'#' => {
+                    while let Some(byte) = self.data.get(self.position) {
+                        if *byte == b'\n' || *byte == b'\r' {
+                            break;
+                        }
+                        self.position += 1;
+                    }
+                }
+                byte if byte.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn read_number(&mut self) -> Result<usize, String> {
+        self.skip_whitespace_and_comments();
+        let start = self.position;
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(|byte| byte.is_ascii_digit())
+        {
+            self.position += 1;
+        }
+        if start == self.position {
+            return Err(format!("expected a number at byte {}", start));
+        }
+        std::str::from_utf8(&self.data[start..self.position])
+            .map_err(|_| format!("invalid number at byte {}", start))?
+            .parse()
+            .map_err(|_| format!("invalid number at byte {}", start))
+    }
+
+    /// P1 rasters may omit the whitespace between bits
+    fn read_bit(&mut self) -> Result<bool, String> {
+        self.skip_whitespace_and_comments();
+        let bit = match self.data.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            Some(_) => return Err(format!("expected 0 or 1 at byte {}", self.position)),
+            None => return Err("unexpected end of pixel data".to_string()),
+        };
+        self.position += 1;
+        Ok(bit)
+    }
+
+    /// binary rasters start after a single whitespace character following the header
+    fn start_raster(&mut self) -> Result<&'a [u8], String> {
+        match self.data.get(self.position) {
+            Some(byte) if byte.is_ascii_whitespace() => Ok(&self.data[self.position + 1..]),
+            _ => Err("expected whitespace before pixel data".to_string()),
+        }
+    }
+}
+
+fn scale_sample(sample: usize, max_value: usize) -> Result<u8, String> {
+    if sample > max_value {
+        return Err(format!(
+            "sample {} exceeds maximum value {}",
+            sample, max_value
+        ));
+    }
+    Ok((sample * 255 / max_value) as u8)
+}
+
+/// the product of header values, which a crafted file can make overflow
+fn size_product(a: usize, b: usize) -> Result<usize, String> {
+    a.checked_mul(b)
+        .ok_or_else(|| format!("image size {}x{} is too big", a, b))
+}
+
+fn read_binary_samples(raster: &[u8], count: usize, max_value: usize) -> Result<Vec<u8>, String> {
+    let bytes_per_sample = if max_value < 256 { 1 } else { 2 };
+    if raster.len() < size_product(count, bytes_per_sample)? {
+        return Err("unexpected end of pixel data".to_string());
+    }
+    raster
+        .chunks(bytes_per_sample)
+        .take(count)
+        .map(|bytes| {
+            let sample = bytes
+                .iter()
+                .fold(0, |acc, byte| (acc << 8) | *byte as usize);
+            scale_sample(sample, max_value)
+        })
+        .collect()
+}
+
+fn samples_to_pixels(samples: Vec<u8>, channels: usize) -> Vec<u8> {
+    match channels {
+        3 => samples
+            .chunks(3)
+            .map(|rgb| luminance(rgb[0], rgb[1], rgb[2]))
+            .collect(),
+        _ => samples,
+    }
+}
+
+/// decodes any of the P1 to P6 netpbm formats into a luminance image
+pub(crate) fn decode(data: &[u8]) -> Result<LumaImage, PicrossError> {
+    read_image(data).map_err(PicrossError::Format)
+}
+
+fn read_image(data: &[u8]) -> Result<LumaImage, String> {
+    if data.len() < 2 || data[0] != b'P' {
+        return Err("not a netpbm file".to_string());
+    }
+    let format = data[1];
+    let mut reader = NetpbmReader::new(&data[2..]);
+    let width = reader.read_number()?;
+    let height = reader.read_number()?;
+    let pixel_count = size_product(width, height)?;
+    let pixels = match format {
+        b'1' => (0..pixel_count)
+            .map(|_| reader.read_bit().map(|bit| if bit { 0 } else { 255 }))
+            .collect::<Result<Vec<u8>, String>>()?,
+        b'4' => {
+            let raster = reader.start_raster()?;
+            let row_bytes = width.div_ceil(8);
+            if raster.len() < size_product(row_bytes, height)? {
+                return Err("unexpected end of pixel data".to_string());
+            }
+            raster
+                .chunks(row_bytes.max(1))
+                .take(height)
+                .flat_map(|row| {
+                    (0..width).map(move |x| {
+                        let bit = (row[x / 8] >> (7 - x % 8)) & 1;
+                        if bit == 1 {
+                            0
+                        } else {
+                            255
+                        }
+                    })
+                })
+                .collect()
+        }
+        b'2' | b'3' | b'5' | b'6' => {
+            let max_value = reader.read_number()?;
+            if max_value == 0 || max_value > 65535 {
+                return Err(format!("invalid maximum value {}", max_value));
+            }
+            let channels = if matches!(format, b'3' | b'6') { 3 } else { 1 };
+            let sample_count = size_product(pixel_count, channels)?;
+            let samples = if matches!(format, b'2' | b'3') {
+                (0..sample_count)
+                    .map(|_| scale_sample(reader.read_number()?, max_value))
+                    .collect::<Result<Vec<u8>, String>>()?
+            } else {
+                read_binary_samples(reader.start_raster()?, sample_count, max_value)?
+            };
+            samples_to_pixels(samples, channels)
+        }
+        _ => return Err(format!("unsupported netpbm format \"P{}\"", format as char)),
+    };
+    Ok(LumaImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ascii_bitmap() {
+        let input = b"P1\n# a comment\n3 2\n1 0 1\n010\n";
+        let image = decode(input).unwrap();
+        assert_eq!(image.width, 3);
+        assert_eq!(image.height, 2);
+        assert_eq!(image.pixels, vec![0, 255, 0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_decode_binary_bitmap() {
+        let mut input = b"P4 10 2\n".to_vec();
+        input.extend([0b1000_0000, 0b0100_0000, 0b0000_0000, 0b1100_0000]);
+        let image = decode(&input).unwrap();
+        let mut expected = vec![255; 20];
+        expected[0] = 0;
+        expected[9] = 0;
+        expected[18] = 0;
+        expected[19] = 0;
+        assert_eq!(image.pixels, expected);
+    }
+
+    #[test]
+    fn test_decode_graymaps() {
+        let ascii = decode(b"P2 2 1 15 0 15").unwrap();
+        assert_eq!(ascii.pixels, vec![0, 255]);
+
+        let mut binary = b"P5 2 1 65535\n".to_vec();
+        binary.extend([0x00, 0x00, 0xff, 0xff]);
+        assert_eq!(decode(&binary).unwrap().pixels, vec![0, 255]);
+    }
+
+    #[test]
+    fn test_decode_pixmaps() {
+        let ascii = decode(b"P3 2 1 255 255 0 0 255 255 255").unwrap();
+        assert_eq!(ascii.pixels, vec![76, 255]);
+
+        let mut binary = b"P6 1 1 255\n".to_vec();
+        binary.extend([0, 0, 255]);
+        assert_eq!(decode(&binary).unwrap().pixels, vec![29]);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(b"GIF89a").is_err());
+        assert!(decode(b"P7 1 1").is_err());
+        assert!(decode(b"P1 2 2 1 0 1").is_err());
+        assert!(decode(b"P2 1 1 3 4").is_err());
+        let Err(PicrossError::Format(message)) = decode(b"P1 99999999999 99999999999 1") else {
+            panic!("the size overflows");
+        };
+        assert!(message.contains("too big"));
+        assert!(decode(b"P6 99999999999 99999999 255\n").is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    env,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    collection::{CollectionEntry, PuzzleCollection},
+    error::PicrossError,
+    image::{bmp, game_from_image, netpbm, ImageOptions},
+    picross::PicrossGame,
+};
+
+/// directories searched for short puzzle names, separated like `PATH`
+pub const SEARCH_PATH_VARIABLE: &str = "PICROSS_PATH";
+/// searched after the directories from `SEARCH_PATH_VARIABLE`
+const DEFAULT_SEARCH_PATH: [&str; 2] = ["./puzzles", "./text_images"];
+
+/// the kinds of file a puzzle can be read from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputFormat {
+    /// clue files, holding one puzzle or a collection
+    Pic,
+    /// rows of "x" and spaces drawing the solution
+    TextImage,
+    /// pbm, pgm and ppm images, plain or raw
+    Netpbm,
+    /// uncompressed bmp images
+    Bmp,
+}
+
+impl InputFormat {
+    const ALL: [InputFormat; 4] = [
+        InputFormat::Pic,
+        InputFormat::TextImage,
+        InputFormat::Netpbm,
+        InputFormat::Bmp,
+    ];
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            InputFormat::Pic => &["pic"],
+            InputFormat::TextImage => &["txt"],
+            InputFormat::Netpbm => &["pbm", "pgm", "ppm", "pnm"],
+            InputFormat::Bmp => &["bmp", "dib"],
+        }
+    }
+
+    /// the format a file extension belongs to, ignoring case
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extensions().contains(&extension.as_str()))
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = PicrossError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pic" => Ok(InputFormat::Pic),
+            "txt" | "text" => Ok(InputFormat::TextImage),
+            "netpbm" | "pbm" | "pgm" | "ppm" | "pnm" => Ok(InputFormat::Netpbm),
+            "bmp" | "dib" => Ok(InputFormat::Bmp),
+            _ => Err(PicrossError::Validation(format!(
+                "invalid format \"{}\"\n Available formats are: pic, txt, netpbm, bmp",
+                s
+            ))),
+        }
+    }
+}
+
+/// the directories from `SEARCH_PATH_VARIABLE` followed by the defaults
+pub fn search_path() -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = env::var_os(SEARCH_PATH_VARIABLE)
+        .map(|paths| env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    directories.extend(DEFAULT_SEARCH_PATH.iter().map(PathBuf::from));
+    directories
+}
+
+/// splits "pack.pic#name" into the file and the puzzle to pick from it,
+/// unless the whole text names an existing file
+pub fn split_selector(source: &str) -> (&str, Option<&str>) {
+    if Path::new(source).exists() {
+        return (source, None);
+    }
+    match source.rsplit_once('#') {
+        Some((file, selector)) => (file, Some(selector)),
+        None => (source, None),
+    }
+}
+
+/// a resolved puzzle source, `path` is `None` for stdin
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    /// the file to read
+    pub path: Option<PathBuf>,
+    /// how to parse what is read
+    pub format: InputFormat,
+}
+
+impl Input {
+    /// `source` is "-" for stdin, a path, or a short name looked up in
+    /// `search_path` with the extensions of `format`, or of every format
+    pub fn resolve(
+        source: &str,
+        format: Option<InputFormat>,
+        default_format: InputFormat,
+        search_path: &[PathBuf],
+    ) -> Result<Self, PicrossError> {
+        if source == "-" {
+            return Ok(Self {
+                path: None,
+                format: format.unwrap_or(default_format),
+            });
+        }
+        let path = Path::new(source);
+        if path.is_file() {
+            return Ok(Self {
+                path: Some(path.to_path_buf()),
+                format: format
+                    .or_else(|| InputFormat::from_path(path))
+                    .unwrap_or(default_format),
+            });
+        }
+        let formats = match format {
+            Some(format) => vec![format],
+            None => std::iter::once(default_format)
+                .chain(InputFormat::ALL)
+                .collect(),
+        };
+        for directory in search_path {
+            for format in formats.iter() {
+                for extension in format.extensions() {
+                    let candidate = directory.join(format!("{}.{}", source, extension));
+                    if candidate.is_file() {
+                        return Ok(Self {
+                            path: Some(candidate),
+                            format: *format,
+                        });
+                    }
+                }
+            }
+        }
+        Err(PicrossError::Validation(format!(
+            "could not find \"{}\" as a file or in {}",
+            source,
+            search_path
+                .iter()
+                .map(|directory| directory.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )))
+    }
+
+    /// the path for messages, or "stdin"
+    pub fn name(&self) -> String {
+        match &self.path {
+            Some(path) => path.display().to_string(),
+            None => "stdin".to_string(),
+        }
+    }
+
+    /// the raw bytes of the file or of stdin
+    pub fn read(&self) -> Result<Vec<u8>, PicrossError> {
+        let mut data = vec![];
+        match &self.path {
+            Some(path) => {
+                data = std::fs::read(path).map_err(|error| PicrossError::io(&self.name(), error))?
+            }
+            None => {
+                io::stdin()
+                    .read_to_end(&mut data)
+                    .map_err(|error| PicrossError::io("stdin", error))?;
+            }
+        }
+        Ok(data)
+    }
+
+    /// reads and parses the input, single puzzle formats give a collection
+    /// holding one puzzle
+    pub fn load(&self, options: &ImageOptions) -> Result<PuzzleCollection, PicrossError> {
+        let data = self.read()?;
+        parse_input(&data, self.format, options).map_err(|error| error.with_context(self.name()))
+    }
+}
+
+pub(crate) fn parse_input(
+    data: &[u8],
+    format: InputFormat,
+    options: &ImageOptions,
+) -> Result<PuzzleCollection, PicrossError> {
+    let text = || {
+        std::str::from_utf8(data)
+            .map_err(|_| PicrossError::Format("puzzle text is not valid UTF-8".to_string()))
+    };
+    let game = match format {
+        InputFormat::Pic => return PuzzleCollection::from_collection_string(text()?),
+        InputFormat::TextImage => PicrossGame::from_text_render(text()?)?,
+        InputFormat::Netpbm => game_from_image(&netpbm::decode(data)?, options)?.0,
+        InputFormat::Bmp => game_from_image(&bmp::decode(data, options.palette_index)?, options)?.0,
+    };
+    Ok(PuzzleCollection(vec![CollectionEntry {
+        name: "1".to_string(),
+        game,
+    }]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            InputFormat::from_path(Path::new("a/b.PIC")),
+            Some(InputFormat::Pic)
+        );
+        assert_eq!(
+            InputFormat::from_path(Path::new("b.pgm")),
+            Some(InputFormat::Netpbm)
+        );
+        assert_eq!(InputFormat::from_path(Path::new("b")), None);
+        assert_eq!(
+            InputFormat::from_str("text").unwrap(),
+            InputFormat::TextImage
+        );
+        assert!(InputFormat::from_str("png").is_err());
+    }
+
+    #[test]
+    fn test_split_selector() {
+        assert_eq!(split_selector("pack#cross"), ("pack", Some("cross")));
+        assert_eq!(split_selector("a#b#2"), ("a#b", Some("2")));
+        assert_eq!(split_selector("pack"), ("pack", None));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let directory = env::temp_dir().join(format!("picross-input-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("cross.pic"), "1,3,1\n-----\n1,3,1").unwrap();
+        std::fs::write(directory.join("dot.txt"), "x").unwrap();
+        let search_path = vec![directory.join("missing"), directory.clone()];
+
+        let input = Input::resolve("cross", None, InputFormat::TextImage, &search_path).unwrap();
+        assert_eq!(input.path, Some(directory.join("cross.pic")));
+        assert_eq!(input.format, InputFormat::Pic);
+        let collection = input.load(&ImageOptions::default()).unwrap();
+        assert_eq!(
+            collection.0[0].game,
+            PicrossGame::from_rules("1,3,1", "1,3,1").unwrap()
+        );
+
+        let path = directory.join("dot.txt");
+        let input =
+            Input::resolve(path.to_str().unwrap(), None, InputFormat::Pic, &search_path).unwrap();
+        assert_eq!(input.format, InputFormat::TextImage);
+        let input = Input::resolve(
+            path.to_str().unwrap(),
+            Some(InputFormat::Pic),
+            InputFormat::Pic,
+            &search_path,
+        )
+        .unwrap();
+        assert_eq!(input.format, InputFormat::Pic);
+
+        assert!(Input::resolve(
+            "dot",
+            Some(InputFormat::Pic),
+            InputFormat::Pic,
+            &search_path
+        )
+        .is_err());
+        assert_eq!(
+            Input::resolve("-", None, InputFormat::Pic, &search_path).unwrap(),
+            Input {
+                path: None,
+                format: InputFormat::Pic
+            }
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let options = ImageOptions::default();
+        let collection = parse_input(b"x \n x", InputFormat::TextImage, &options).unwrap();
+        assert_eq!(
+            collection.0[0].game,
+            PicrossGame::from_rules("1,1", "1,1").unwrap()
+        );
+        let collection = parse_input(b"P1 2 1 1 0", InputFormat::Netpbm, &options).unwrap();
+        assert_eq!(
+            collection.0[0].game,
+            PicrossGame::from_rules("1", "1,0").unwrap()
+        );
+        assert!(parse_input(&[0xff, 0xfe], InputFormat::Pic, &options).is_err());
+    }
+}
@@ -1,7 +1,11 @@
-use crate::game_board::{GameBoardRow, Segment, TileState};
+use crate::{
+    error::PicrossError,
+    game_board::{GameBoardRow, Segment, TileState},
+};
 
+/// every arrangement of a line's clues that fits in `width` tiles
 #[derive(Debug, Clone)]
-pub struct PicrossLineIter<'a> {
+pub(crate) struct PicrossLineIter<'a> {
     width: usize,
     stack: Vec<RowIterFrame<'a>>,
 }
@@ -14,7 +18,7 @@ struct RowIterFrame<'a> {
 }
 
 impl<'a> PicrossLineIter<'a> {
-    pub fn new(chunks: &'a [usize], width: usize) -> Self {
+    pub(crate) fn new(chunks: &'a [usize], width: usize) -> Self {
         Self {
             width,
             stack: vec![RowIterFrame {
@@ -24,10 +28,10 @@ impl<'a> PicrossLineIter<'a> {
             }],
         }
     }
-    pub fn get_partially_solved_line(
+    pub(crate) fn get_partially_solved_line(
         &mut self,
         known_row: Option<&GameBoardRow>,
-    ) -> Result<GameBoardRow, &'static str> {
+    ) -> Result<GameBoardRow, PicrossError> {
         let default = GameBoardRow(vec![TileState::Undetermined; self.width]);
         let compare_row = known_row.unwrap_or(&default);
         self.filter(|row| {
@@ -51,7 +55,7 @@ impl<'a> PicrossLineIter<'a> {
                 .collect();
             GameBoardRow(row)
         })
-        .ok_or("no valid configurations")
+        .ok_or_else(|| PicrossError::Contradiction("no valid configurations".to_string()))
     }
 }
 
@@ -61,14 +65,15 @@ impl Iterator for PicrossLineIter<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(frame) = self.stack.pop() {
             if frame.remaining_chunks.is_empty() {
-                return Some(
-                    GameBoardRow::build_from_segments(frame.current_solution, self.width).unwrap(),
-                );
+                return Some(GameBoardRow::build_from_segments(
+                    frame.current_solution,
+                    self.width,
+                ));
             }
 
             let current_chunk_length = frame.remaining_chunks[0];
             if current_chunk_length == 0 {
-                return Some(GameBoardRow::build_from_segments(vec![], self.width).unwrap());
+                return Some(GameBoardRow::build_from_segments(vec![], self.width));
             }
             let others = &frame.remaining_chunks[1..];
 
@@ -164,8 +169,8 @@ mod tests {
         let width = 10;
         let mut row_iter = PicrossLineIter::new(&[6], width);
         assert_eq!(
-            row_iter.get_partially_solved_line(None),
-            Ok(GameBoardRow(vec![
+            row_iter.get_partially_solved_line(None).unwrap(),
+            GameBoardRow(vec![
                 Undetermined,
                 Undetermined,
                 Undetermined,
@@ -176,15 +181,22 @@ mod tests {
                 Undetermined,
                 Undetermined,
                 Undetermined,
-            ]))
+            ])
         );
 
         let mut row_iter = PicrossLineIter::new(&[0], width);
         assert_eq!(
-            row_iter.get_partially_solved_line(None),
-            Ok(GameBoardRow(vec![
+            row_iter.get_partially_solved_line(None).unwrap(),
+            GameBoardRow(vec![
                 Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty,
-            ]))
-        )
+            ])
+        );
+
+        let mut row_iter = PicrossLineIter::new(&[3], 4);
+        let known = GameBoardRow(vec![Filled, Empty, Undetermined, Undetermined]);
+        assert!(matches!(
+            row_iter.get_partially_solved_line(Some(&known)),
+            Err(PicrossError::Contradiction(_))
+        ));
     }
 }
@@ -0,0 +1,64 @@
+//! Solving, generating and exporting picross (nonogram) puzzles.
+//!
+//! A [`PicrossGame`] holds the row and column clues, a [`GameBoard`] holds the
+//! tiles, and any [`PicrossSolver`] turns the first into a solved
+//! [`PicrossFrame`]:
+//!
+//! ```
+//! use picross_solver::{GameState, PicrossGame, PicrossSolver, PicrossSolverV4};
+//!
+//! let game = PicrossGame::from_rules_file_string("1 1\n1\n1 1\n-----\n1 1\n1\n1 1").unwrap();
+//! let solver = PicrossSolverV4::from_game(game);
+//! let frame = solver.solve_with_observer(&mut Vec::new()).unwrap();
+//! assert_eq!(frame.game_state, GameState::Complete);
+//! assert_eq!(frame.board.render(), "██  ██\n  ██  \n██  ██");
+//! ```
+//!
+//! Every fallible function returns a [`PicrossError`].
+
+mod analysis;
+mod collection;
+mod error;
+mod export;
+mod game_board;
+mod generate;
+mod image;
+mod input;
+mod iterators;
+mod picross;
+mod render;
+mod sat;
+
+pub use analysis::{
+    broken_lines, check_uniqueness, find_solutions, rate, Difficulty, Rating, Uniqueness,
+};
+pub use collection::{CollectionEntry, PuzzleCollection, SEPARATOR};
+pub use error::{ParseError, PicrossError};
+pub use export::{
+    asciicast::AsciicastRecorder,
+    gif::{encode_gif, GifOptions, GifRecorder},
+    html::render_html,
+    json::render_json,
+    png::{render_board_png, render_frame_png},
+    svg::{render_svg, SvgOptions},
+};
+pub use game_board::{GameBoard, TileState};
+pub use generate::{generate, GeneratorOptions};
+pub use image::{parse_dimensions, ImageOptions, ScaleMode};
+pub use input::{search_path, split_selector, Input, InputFormat, SEARCH_PATH_VARIABLE};
+pub use picross::{
+    metadata::{CommentPosition, PuzzleMetadata},
+    picross_solver_v1::PicrossSolverV1,
+    picross_solver_v2::PicrossSolverV2,
+    picross_solver_v3::PicrossSolverV3,
+    picross_solver_v4::PicrossSolverV4,
+    AxisRules, LineRule, PicrossGame, PicrossSolver, SolveReport,
+};
+pub use render::{
+    AnimationOptions, ClueLayout, FrameObserver, GameState, PacedPrinter, PicrossFrame,
+    TerminalPrinter, Viewport, MIN_FPS,
+};
+pub use sat::{
+    cdcl::SatStatistics,
+    cnf::{board_from_model, game_to_dimacs, parse_model},
+};
@@ -1,47 +1,832 @@
 use app::App;
-use clap::Parser;
-use std::error;
+use clap::{
+    builder::{RangedU64ValueParser, Resettable},
+    Args, Parser, Subcommand,
+};
+use picross_solver::{
+    check_uniqueness, find_solutions, game_to_dimacs, generate, parse_dimensions, rate,
+    render_board_png, render_json, render_svg, AnimationOptions, AsciicastRecorder,
+    CollectionEntry, CommentPosition, FrameObserver, GameBoard, GameState, GeneratorOptions,
+    GifOptions, ImageOptions, InputFormat, PacedPrinter, PicrossError, PicrossGame,
+    PuzzleCollection, ScaleMode, SolveReport, SvgOptions, Uniqueness, MIN_FPS, SEPARATOR,
+};
+use std::{
+    fs::write,
+    io::{self, IsTerminal, Write},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
 
 mod app;
-mod game_board;
-mod iterators;
-mod picross;
-mod render;
+mod play;
+
+/// clap exits with 2 for invalid arguments too
+const EXIT_INVALID_INPUT: i32 = 2;
+const EXIT_UNSOLVABLE: i32 = 3;
+const EXIT_IO: i32 = 4;
+
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success
+  2  invalid arguments or puzzle input, or a puzzle without a unique solution
+  3  a puzzle that cannot be solved
+  4  a file that could not be read or written";
 
 /// A program to solve Picross Puzzles
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, after_help = EXIT_CODES_HELP)]
 struct Cli {
-    /// set the solver version
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// solve a puzzle, animating the solver in the terminal
+    Solve(Box<SolveArgs>),
+    /// check that every puzzle in a file parses and has a solution
+    Validate(InputArgs),
+    /// convert a puzzle to another format
+    Convert(ConvertArgs),
+    /// generate a random puzzle as a .pic file
+    Generate(GenerateArgs),
+    /// rate how hard puzzles are to solve by hand
+    Rate(InputArgs),
+    /// check that every puzzle in a file has exactly one solution
+    CheckUnique(CheckUniqueArgs),
+    /// time the solvers on every puzzle in a file
+    Bench(BenchArgs),
+    /// play a puzzle in the terminal, with the keyboard or the mouse
+    #[command(mut_arg("puzzle", |arg| {
+        arg.required_unless_present(Resettable::Reset)
+            .help("puzzle to play, like for solve, or pick one from a menu when left out")
+    }))]
+    Play(PlayArgs),
+}
+
+#[derive(Args, Debug)]
+struct InputArgs {
+    /// puzzle file, "-" for stdin, or a name searched for in $PICROSS_PATH,
+    /// ./puzzles and ./text_images; add #name or #index to pick from a collection
+    #[arg(required_unless_present_any = ["rows", "clues"])]
+    puzzle: Option<String>,
+
+    /// row clues instead of a puzzle file, like "1 1,1,1 1"
+    #[arg(long, requires = "cols", conflicts_with_all = ["puzzle", "clues"])]
+    rows: Option<String>,
+
+    /// column clues to go with --rows
+    #[arg(long, requires = "rows")]
+    cols: Option<String>,
+
+    /// row and column clues separated by a slash, like "1 1,1,1 1 / 1 1,1,1 1"
+    #[arg(long, conflicts_with = "puzzle")]
+    clues: Option<String>,
+
+    /// input format: pic, txt, netpbm or bmp, detected from the extension by default
+    #[arg(long)]
+    format: Option<String>,
+
+    /// luminance (0-255) below which image pixels become filled tiles
+    #[arg(long, default_value_t = 128)]
+    threshold: u8,
+
+    /// downscale the image to a WIDTHxHEIGHT grid
+    #[arg(long)]
+    resize: Option<String>,
+
+    /// resize sampling: nearest or box
+    #[arg(long, default_value = "nearest")]
+    scale_mode: String,
+
+    /// treat bmp pixels with this palette index as filled instead of using the threshold
+    #[arg(long)]
+    palette_index: Option<u8>,
+}
+
+impl InputArgs {
+    fn format_and_options(&self) -> Result<(Option<InputFormat>, ImageOptions), PicrossError> {
+        let format = self
+            .format
+            .as_deref()
+            .map(InputFormat::from_str)
+            .transpose()?;
+        let options = ImageOptions {
+            threshold: self.threshold,
+            resize: self.resize.as_deref().map(parse_dimensions).transpose()?,
+            scale_mode: ScaleMode::from_str(&self.scale_mode)?,
+            palette_index: self.palette_index,
+        };
+        Ok((format, options))
+    }
+
+    /// the puzzle from --rows and --cols or --clues, if given
+    fn inline_game(&self) -> Result<Option<PicrossGame>, PicrossError> {
+        match (&self.rows, &self.cols, &self.clues) {
+            (Some(rows), Some(cols), _) => PicrossGame::from_rules(rows, cols)
+                .map(Some)
+                .map_err(|error| error.with_context("--rows and --cols")),
+            (_, _, Some(clues)) => PicrossGame::from_clues(clues)
+                .map(Some)
+                .map_err(|error| error.with_context("--clues")),
+            _ => Ok(None),
+        }
+    }
+
+    fn puzzle(&self) -> Result<&str, PicrossError> {
+        self.puzzle.as_deref().ok_or_else(|| {
+            PicrossError::Validation(
+                "a puzzle, --rows and --cols, or --clues is needed".to_string(),
+            )
+        })
+    }
+
+    fn read_puzzles(&self, app: &App) -> Result<PuzzleCollection, PicrossError> {
+        let collection = match self.inline_game()? {
+            Some(game) => PuzzleCollection(vec![CollectionEntry {
+                name: "clues".to_string(),
+                game,
+            }]),
+            None => {
+                let (format, options) = self.format_and_options()?;
+                app.read_puzzles(self.puzzle()?, format, InputFormat::Pic, &options)?
+            }
+        };
+        Ok(collection)
+    }
+
+    /// selects the one puzzle the arguments name in `app`
+    fn select_game(&self, app: &mut App) -> Result<PicrossGame, PicrossError> {
+        match self.inline_game()? {
+            Some(game) => app.set_game(game),
+            None => {
+                let (format, options) = self.format_and_options()?;
+                app.select_game(self.puzzle()?, format, InputFormat::Pic, &options)?;
+            }
+        }
+        let game = app
+            .game()
+            .cloned()
+            .ok_or_else(|| PicrossError::Validation("no puzzle selected".to_string()))?;
+        Ok(game)
+    }
+}
+
+/// writes `contents` to the --save path, if one was given
+fn save(path: Option<&str>, contents: String) -> Result<(), PicrossError> {
+    match path {
+        Some(path) => write(path, contents + "\n").map_err(|error| PicrossError::io(path, error)),
+        None => Ok(()),
+    }
+}
+
+/// saves a collection of one puzzle as a plain .pic file
+fn save_puzzles(path: Option<&str>, collection: &PuzzleCollection) -> Result<(), PicrossError> {
+    match collection.0.as_slice() {
+        [entry] => save(path, entry.game.to_rules_file_string()),
+        _ => save(path, collection.to_collection_string()),
+    }
+}
+
+#[derive(Args, Debug)]
+struct SolveArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// set the solver version: v1, v2, v3 or v4
     #[arg(short, long)]
     solver_version: Option<String>,
 
+    /// write the puzzle to this .pic file, handy with --rows or --clues
+    #[arg(long)]
+    save: Option<String>,
+
+    /// solve every puzzle in the collection and print a summary
+    #[arg(long, conflicts_with_all = ["svg", "html", "png", "gif", "cast", "model"])]
+    all: bool,
+
+    /// how to print the result: text, json, pic or image, only text animates
+    #[arg(long, default_value = "text")]
+    output: String,
+
+    /// print only the result instead of animating every solver step, the
+    /// default when stdout isn't a terminal
+    #[arg(long)]
+    no_animate: bool,
+
+    /// the most animation frames drawn per second
+    #[arg(long)]
+    fps: Option<f64>,
+
+    /// draw only every nth solver step
+    #[arg(long, default_value_t = 1, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    every_n_steps: usize,
+
+    /// wait for a key press after every drawn frame, q runs to the end
+    #[arg(long)]
+    step: bool,
+
+    /// animate with a character per tile and no clues, for boards bigger
+    /// than the terminal
+    #[arg(long)]
+    compact: bool,
+
+    /// draw lines between every 5 rows and columns of the board
+    #[arg(long)]
+    guides: bool,
+
+    /// print nothing but errors, the exit code tells whether the puzzle was solved
     #[arg(short, long)]
-    puzzle: Option<String>,
+    quiet: bool,
+
+    /// write a printable svg puzzle sheet with the solution to this path
+    #[arg(long)]
+    svg: Option<String>,
+
+    /// write a playable html page to this path after solving
+    #[arg(long)]
+    html: Option<String>,
+
+    /// write the solved board as a png to this path
+    #[arg(long)]
+    png: Option<String>,
+
+    /// png pixels per tile
+    #[arg(long, default_value_t = 10)]
+    png_scale: usize,
+
+    /// include the clues and grid in the png
+    #[arg(long)]
+    png_clues: bool,
+
+    /// record the solve as an animated gif at this path
+    #[arg(long)]
+    gif: Option<String>,
+
+    /// gif pixels per tile
+    #[arg(long, default_value_t = 10)]
+    gif_scale: usize,
+
+    /// delay between gif frames in hundredths of a second
+    #[arg(long, default_value_t = 5)]
+    gif_delay: u16,
 
+    /// solver frames to drop after each recorded gif frame
+    #[arg(long, default_value_t = 0)]
+    gif_frame_skip: usize,
+
+    /// maximum number of gif frames, the final board is always included
+    #[arg(long, default_value_t = 500)]
+    gif_max_frames: usize,
+
+    /// record the terminal output of the solve as an asciicast v2 file at this path
+    #[arg(long)]
+    cast: Option<String>,
+
+    /// read a SAT solver model for the DIMACS encoding and check it against the puzzle
+    #[arg(long)]
+    model: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ConvertArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// output format: pic, txt, svg, dimacs or png, txt and png hold the solution
+    #[arg(long)]
+    to: String,
+
+    /// write to this path instead of stdout
     #[arg(short, long)]
-    image: Option<String>,
+    out: Option<String>,
+
+    /// write the puzzle to this .pic file, handy with --rows or --clues
+    #[arg(long)]
+    save: Option<String>,
+
+    /// png pixels per tile
+    #[arg(long, default_value_t = 10)]
+    png_scale: usize,
 }
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    let args = Cli::parse();
+#[derive(Args, Debug)]
+struct GenerateArgs {
+    #[arg(long, default_value_t = 10)]
+    width: usize,
 
-    let mut app = App::new();
+    #[arg(long, default_value_t = 10)]
+    height: usize,
+
+    /// chance of each tile being filled, from 0 to 1
+    #[arg(long, default_value_t = 0.55)]
+    density: f64,
+
+    /// seed for the random board, taken from the clock by default
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// only accept puzzles with exactly one solution
+    #[arg(long)]
+    unique: bool,
+
+    /// boards to try before giving up on a unique puzzle
+    #[arg(long, default_value_t = 100)]
+    attempts: usize,
+
+    /// title written into the puzzle's metadata
+    #[arg(long)]
+    title: Option<String>,
+
+    /// write to this path instead of stdout
+    #[arg(short, long)]
+    out: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct CheckUniqueArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// print the solution, or two different solutions
+    #[arg(long)]
+    show: bool,
+}
+
+#[derive(Args, Debug)]
+struct PlayArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// session file to resume from and save to, named after the puzzle in the
+    /// current directory by default, resumes its puzzle when none is given
+    #[arg(long)]
+    session: Option<String>,
+
+    /// mistake checking: off, check to find wrong tiles when checking the
+    /// board with ?, or instant to flag wrong fills as they are made
+    #[arg(long, default_value = "off")]
+    assist: String,
+}
+
+#[derive(Args, Debug)]
+struct BenchArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// comma separated solver versions to time, v1 tries every layout and
+    /// only finishes small puzzles, so it has to be asked for
+    #[arg(long, default_value = "v2,v3,v4")]
+    solvers: String,
+
+    /// timed solves per solver and puzzle
+    #[arg(long, default_value_t = 3)]
+    runs: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Pic,
+    Image,
+}
+
+impl FromStr for OutputFormat {
+    type Err = PicrossError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "pic" => Ok(OutputFormat::Pic),
+            "image" => Ok(OutputFormat::Image),
+            _ => Err(PicrossError::Validation(format!(
+                "invalid output \"{}\"\n Available outputs are: text, json, pic, image",
+                s
+            ))),
+        }
+    }
+}
+
+fn main() {
+    match run(Cli::parse()) {
+        // the reader of a pipe like `| head` has stopped, nothing went wrong
+        Err(PicrossError::Io { source, .. }) if source.kind() == io::ErrorKind::BrokenPipe => (),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            std::process::exit(exit_code(&error));
+        }
+        Ok(()) => (),
+    }
+}
+
+fn exit_code(error: &PicrossError) -> i32 {
+    match error {
+        PicrossError::Context { source, .. } => exit_code(source),
+        PicrossError::Contradiction(_) => EXIT_UNSOLVABLE,
+        PicrossError::Io { .. } => EXIT_IO,
+        _ => EXIT_INVALID_INPUT,
+    }
+}
+
+fn run(args: Cli) -> Result<(), PicrossError> {
+    match args.command {
+        Command::Solve(args) => solve(*args),
+        Command::Validate(args) => validate(args),
+        Command::Convert(args) => convert(args),
+        Command::Generate(args) => generate_puzzle(args),
+        Command::Rate(args) => rate_puzzles(args),
+        Command::CheckUnique(args) => check_unique(args),
+        Command::Bench(args) => bench(args),
+        Command::Play(args) => play_puzzle(args),
+    }
+}
+
+fn not_solved() -> PicrossError {
+    PicrossError::Contradiction("the puzzle could not be solved".to_string())
+}
+
+/// writes to `path`, or to stdout without one
+fn write_output(path: Option<&str>, contents: &[u8]) -> Result<(), PicrossError> {
+    match path {
+        Some(path) => write(path, contents).map_err(|error| PicrossError::io(path, error)),
+        None => io::stdout()
+            .write_all(contents)
+            .map_err(|error| PicrossError::io("stdout", error)),
+    }
+}
+
+/// the text printed for a finished solve, `None` when the animation already
+/// showed it
+fn report_output(
+    name: &str,
+    solver: &str,
+    report: &SolveReport,
+    output: OutputFormat,
+    guides: bool,
+) -> Option<String> {
+    let frame = &report.frame;
+    match output {
+        OutputFormat::Text => Some(format!(
+            "{}\n\nElapsed time: {:?}",
+            frame.render_with_guides(guides),
+            report.elapsed
+        )),
+        OutputFormat::Json => Some(render_json(
+            name,
+            frame,
+            solver,
+            report.elapsed,
+            &report.statistics,
+        )),
+        OutputFormat::Pic => Some(frame.game().to_rules_file_string()),
+        OutputFormat::Image => Some(frame.board.to_text_image()),
+    }
+}
 
+/// prints a line to stdout, without panicking when the reader goes away
+fn print_output(text: &str) -> Result<(), PicrossError> {
+    write_output(None, format!("{}\n", text).as_bytes())
+}
+
+fn solve(args: SolveArgs) -> Result<(), PicrossError> {
+    let mut app = App::new();
+    app.set_guides(args.guides);
     if let Some(version) = args.solver_version {
         app.change_version(&version)?;
     }
+    let output = OutputFormat::from_str(&args.output)?;
+    let animate = output == OutputFormat::Text
+        && !args.no_animate
+        && !args.quiet
+        && io::stdout().is_terminal();
+    if args
+        .fps
+        .is_some_and(|fps| !(MIN_FPS..f64::INFINITY).contains(&fps))
+    {
+        return Err(PicrossError::Validation(format!(
+            "--fps must be a number of at least {}",
+            MIN_FPS
+        )));
+    }
+    let options = AnimationOptions {
+        fps: args.fps,
+        every_n_steps: args.every_n_steps,
+        step: args.step,
+        compact: args.compact,
+        guides: args.guides,
+    };
+    // the cast records what the printer draws, even when it isn't shown
+    let mut printer = match args.cast {
+        Some(_) => {
+            let (width, height) = termion::terminal_size().unwrap_or((80, 24));
+            PacedPrinter::recording(options, AsciicastRecorder::new(width, height), animate)
+        }
+        None => PacedPrinter::new(options),
+    };
+    let mut no_observer = Vec::<&mut dyn FrameObserver>::new();
+    let observer: &mut dyn FrameObserver = match animate || args.cast.is_some() {
+        true => &mut printer,
+        false => &mut no_observer,
+    };
 
-    if let Some(puzzle) = args.puzzle {
-        app.select_game_from_puzzles(&puzzle)?;
-        app.solve()?;
-        return Ok(());
+    if args.all {
+        let collection = args.input.read_puzzles(&app)?;
+        save_puzzles(args.save.as_deref(), &collection)?;
+        let solver = app.version.name();
+        let results = collection.solve_all(&mut app.version, observer);
+        let mut all_solved = true;
+        let mut outputs = vec![];
+        for (name, result) in results {
+            match result {
+                Ok(report) => {
+                    all_solved &= report.frame.game_state == GameState::Complete;
+                    outputs.push(match output {
+                        OutputFormat::Text => format!("{}: {:?}", name, report.frame.game_state),
+                        OutputFormat::Json => {
+                            report_output(name, solver, &report, output, args.guides)
+                                .unwrap_or_default()
+                        }
+                        _ => format!(
+                            "{} {}\n{}",
+                            SEPARATOR,
+                            name,
+                            report_output(name, solver, &report, output, args.guides)
+                                .unwrap_or_default()
+                        ),
+                    })
+                }
+                Err(error) => {
+                    all_solved = false;
+                    eprintln!("{}: {}", name, error)
+                }
+            }
+        }
+        if !args.quiet {
+            match output {
+                OutputFormat::Json => print_output(&format!("[{}]", outputs.join(",\n")))?,
+                _ => print_output(&outputs.join("\n"))?,
+            }
+        }
+        return match all_solved {
+            true => Ok(()),
+            false => Err(PicrossError::Contradiction(
+                "some puzzles could not be solved".to_string(),
+            )),
+        };
+    }
+
+    let game = args.input.select_game(&mut app)?;
+    save(args.save.as_deref(), game.to_rules_file_string())?;
+    let title = match (&game.metadata.title, &args.input.puzzle) {
+        (Some(title), _) | (None, Some(title)) => title.clone(),
+        (None, None) => "Picross".to_string(),
+    };
+
+    if let Some(model_path) = args.model {
+        let report = SolveReport {
+            frame: app.read_model(&model_path)?,
+            elapsed: Duration::ZERO,
+            statistics: vec![],
+        };
+        let text = match output {
+            OutputFormat::Text => Some(report.frame.render_with_guides(args.guides)),
+            _ => report_output(&title, "model", &report, output, args.guides),
+        };
+        if let Some(text) = text.filter(|_| !args.quiet) {
+            print_output(&text)?;
+        }
+        return match report.frame.game_state {
+            GameState::Complete => Ok(()),
+            _ => Err(PicrossError::Contradiction(
+                "the model does not solve the puzzle".to_string(),
+            )),
+        };
+    }
+    let report = match args.gif {
+        Some(gif_path) => {
+            let options = GifOptions {
+                scale: args.gif_scale,
+                delay: args.gif_delay,
+                frame_skip: args.gif_frame_skip,
+                max_frames: args.gif_max_frames,
+            };
+            app.solve_recording_gif(&gif_path, options, observer)?
+        }
+        None => app.solve_with_observer(observer)?,
+    };
+    if let (Some(cast_path), Some(recorder)) = (args.cast, printer.into_recording()) {
+        write_output(Some(&cast_path), recorder.to_cast_string().as_bytes())?;
+    }
+    if !args.quiet && !animate {
+        if let Some(text) = report_output(&title, app.version.name(), &report, output, args.guides)
+        {
+            print_output(&text)?;
+        }
     }
-    if let Some(image_file) = args.image {
-        app.select_game_from_text_image(&image_file)?;
-        app.solve()?;
-        return Ok(());
+    let result = report.frame;
+    if result.game_state != GameState::Complete {
+        return Err(not_solved());
+    }
+    if let Some(svg_path) = args.svg {
+        app.export_svg(&svg_path, Some(&result.board))?;
+    }
+    if let Some(html_path) = args.html {
+        app.export_html(&html_path, &result.board, &title)?;
+    }
+    if let Some(png_path) = args.png {
+        app.export_png(&png_path, &result.board, args.png_scale, args.png_clues)?;
     }
-    println!("Either puzzle or image argument must be provided");
     Ok(())
 }
+
+fn validate(args: InputArgs) -> Result<(), PicrossError> {
+    let collection = args.read_puzzles(&App::new())?;
+    let mut unsolvable = 0;
+    for entry in collection.iter() {
+        let game = &entry.game;
+        match find_solutions(game, 1).is_empty() {
+            true => {
+                unsolvable += 1;
+                println!("{}: no solution", entry.name);
+            }
+            false => println!("{}: ok, {}x{}", entry.name, game.width(), game.height()),
+        }
+    }
+    match unsolvable {
+        0 => Ok(()),
+        count => Err(PicrossError::Contradiction(format!(
+            "{} of {} puzzles have no solution",
+            count,
+            collection.len()
+        ))),
+    }
+}
+
+fn convert(args: ConvertArgs) -> Result<(), PicrossError> {
+    let game = args.input.select_game(&mut App::new())?;
+    save(args.save.as_deref(), game.to_rules_file_string())?;
+    let solution = || -> Result<GameBoard, PicrossError> {
+        find_solutions(&game, 1).pop().ok_or_else(not_solved)
+    };
+    let output = match args.to.as_str() {
+        "pic" => game.to_rules_file_string() + "\n",
+        "txt" => solution()?.to_text_image() + "\n",
+        "svg" => render_svg(&game, None, &SvgOptions::default()),
+        "dimacs" => game_to_dimacs(&game),
+        "png" => {
+            let png = render_board_png(&solution()?, args.png_scale);
+            return write_output(args.out.as_deref(), &png);
+        }
+        format => {
+            return Err(PicrossError::Validation(format!(
+                "invalid output format \"{}\"\n Available formats are: pic, txt, svg, dimacs, png",
+                format
+            )))
+        }
+    };
+    write_output(args.out.as_deref(), output.as_bytes())
+}
+
+fn generate_puzzle(args: GenerateArgs) -> Result<(), PicrossError> {
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    let options = GeneratorOptions {
+        width: args.width,
+        height: args.height,
+        density: args.density,
+        seed,
+        unique: args.unique,
+        max_attempts: args.attempts,
+    };
+    let mut game = generate(&options)?;
+    game.metadata.title = args.title;
+    let comment = format!(
+        "generated with --width {} --height {} --density {} --seed {}{}",
+        args.width,
+        args.height,
+        args.density,
+        seed,
+        if args.unique { " --unique" } else { "" }
+    );
+    game.metadata
+        .comments
+        .push((CommentPosition::Header, comment));
+    let output = game.to_rules_file_string() + "\n";
+    write_output(args.out.as_deref(), output.as_bytes())
+}
+
+fn rate_puzzles(args: InputArgs) -> Result<(), PicrossError> {
+    let collection = args.read_puzzles(&App::new())?;
+    for entry in collection.iter() {
+        let rating = rate(&entry.game).map_err(|error| error.with_context(&entry.name))?;
+        let tiles = entry.game.width() * entry.game.height();
+        println!(
+            "{}: {} (line solving: {} sweeps, {}/{} tiles; {}; {} decisions, {} conflicts)",
+            entry.name,
+            rating.difficulty,
+            rating.sweeps,
+            rating.line_solved_tiles,
+            tiles,
+            if rating.unique {
+                "unique"
+            } else {
+                "not unique"
+            },
+            rating.statistics.decisions,
+            rating.statistics.conflicts,
+        );
+    }
+    Ok(())
+}
+
+fn check_unique(args: CheckUniqueArgs) -> Result<(), PicrossError> {
+    let collection = args.input.read_puzzles(&App::new())?;
+    let (mut unsolvable, mut ambiguous) = (0, 0);
+    for entry in collection.iter() {
+        match check_uniqueness(&entry.game) {
+            Uniqueness::Unique(solution) => {
+                println!("{}: unique", entry.name);
+                if args.show {
+                    println!("{}\n", solution.render());
+                }
+            }
+            Uniqueness::Multiple(first, second) => {
+                ambiguous += 1;
+                println!("{}: more than one solution", entry.name);
+                if args.show {
+                    println!("{}\n\n{}\n", first.render(), second.render());
+                }
+            }
+            Uniqueness::Unsolvable => {
+                unsolvable += 1;
+                println!("{}: no solution", entry.name);
+            }
+        }
+    }
+    match (unsolvable, ambiguous) {
+        (0, 0) => Ok(()),
+        (0, count) => Err(PicrossError::Validation(format!(
+            "{} of {} puzzles have more than one solution",
+            count,
+            collection.len()
+        ))),
+        (count, _) => Err(PicrossError::Contradiction(format!(
+            "{} of {} puzzles have no solution",
+            count,
+            collection.len()
+        ))),
+    }
+}
+
+fn bench(args: BenchArgs) -> Result<(), PicrossError> {
+    let mut app = App::new();
+    let collection = args.input.read_puzzles(&app)?;
+    for entry in collection.iter() {
+        for version in args.solvers.split(',').map(str::trim) {
+            app.change_version(version)?;
+            app.set_game(entry.game.clone());
+            let mut times = vec![];
+            let mut game_state = GameState::InProgress;
+            for _ in 0..args.runs.max(1) {
+                let report = app.solve_with_observer(&mut Vec::<&mut dyn FrameObserver>::new())?;
+                times.push(report.elapsed);
+                game_state = report.frame.game_state;
+            }
+            let total: Duration = times.iter().sum();
+            println!(
+                "{} {}: {:?}, min {:?}, mean {:?}",
+                entry.name,
+                version,
+                game_state,
+                times.iter().min().copied().unwrap_or_default(),
+                total / times.len() as u32,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn play_puzzle(args: PlayArgs) -> Result<(), PicrossError> {
+    let assist = play::Assist::from_str(&args.assist)?;
+    let input = &args.input;
+    let puzzle = match input.puzzle.is_some() || input.rows.is_some() || input.clues.is_some() {
+        true => {
+            let game = input.select_game(&mut App::new())?;
+            let name = game
+                .metadata
+                .title
+                .clone()
+                .or_else(|| input.puzzle.clone())
+                .unwrap_or_else(|| "clues".to_string());
+            Some((name, game))
+        }
+        false => None,
+    };
+    play::play(puzzle, args.session.map(Into::into), assist)
+}
@@ -1,88 +1,143 @@
 use crate::{
+    error::{ParseError, PicrossError},
     game_board::{GameBoard, GameBoardRow},
     iterators::PicrossLineIter,
-    render::PicrossFrame,
+    render::{FrameObserver, PicrossFrame, TerminalPrinter},
+};
+use metadata::{CommentPosition, PuzzleMetadata};
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
 };
-use std::str::FromStr;
 
-pub mod picross_solver_v1;
-pub mod picross_solver_v2;
-pub mod picross_solver_v3;
+pub(crate) mod metadata;
+pub(crate) mod picross_solver_v1;
+pub(crate) mod picross_solver_v2;
+pub(crate) mod picross_solver_v3;
+pub(crate) mod picross_solver_v4;
 
 const DIVIDER: &str = "-----";
 
+/// the clue for one row or column, the lengths of its filled runs in order,
+/// `[0]` for a line with no filled tiles
 #[derive(Debug, PartialEq, Clone)]
 pub struct LineRule(pub Vec<usize>);
 
-#[allow(dead_code)]
+const CLUES_EXPECTED: &str = "clue numbers separated by spaces";
+
 impl LineRule {
-    fn from_render_line(input: &str) -> Self {
-        let rule: Vec<usize> = input
-            .chars()
-            .enumerate()
-            .fold(
-                (false, 0, vec![]),
-                |(is_collecting, count, mut rule), (index, char)| {
-                    let is_last_char = index + 1 == input.len();
-                    match char {
-                        'x' | 'X' => {
-                            if is_last_char {
-                                rule.push(count + 1);
-                                return (false, 0, rule);
-                            }
-                            return (true, count + 1, rule);
-                        }
-                        ' ' => {
-                            if is_last_char && rule.len() == 0 && count == 0 {
-                                rule.push(0);
-                                return (false, 0, rule);
-                            }
-                            if is_collecting && (count != 0) {
-                                rule.push(count);
-                            }
-                            return (false, 0, rule);
-                        }
-                        _ => panic!(),
+    fn from_render_line(input: &str, line: usize) -> Result<Self, ParseError> {
+        let mut rule = vec![];
+        let mut count = 0;
+        for (index, char) in input.chars().enumerate() {
+            match char {
+                'x' | 'X' => count += 1,
+                ' ' => {
+                    if count != 0 {
+                        rule.push(count);
                     }
-                },
-            )
-            .2;
-        Self(rule)
+                    count = 0;
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        input,
+                        line,
+                        index + 1,
+                        &char.to_string(),
+                        "\"x\" for a filled tile or a space for an empty one",
+                    ))
+                }
+            }
+        }
+        if count != 0 || rule.is_empty() {
+            rule.push(count);
+        }
+        Ok(Self(rule))
+    }
+
+    /// parses the clues in `text`, which starts at `column` of `source_line`
+    fn parse_at(
+        text: &str,
+        source_line: &str,
+        line: usize,
+        column: usize,
+    ) -> Result<Self, ParseError> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut rule = vec![];
+        let mut token_start = None;
+        for (index, char) in chars.iter().chain([' '].iter()).enumerate() {
+            match (char.is_whitespace(), token_start) {
+                (false, None) => token_start = Some(index),
+                (true, Some(start)) => {
+                    let token: String = chars[start..index].iter().collect();
+                    let clue = token.parse().map_err(|_| {
+                        ParseError::new(source_line, line, column + start, &token, CLUES_EXPECTED)
+                    })?;
+                    rule.push(clue);
+                    token_start = None;
+                }
+                _ => (),
+            }
+        }
+        if rule.is_empty() {
+            let end = column + chars.len();
+            return Err(ParseError::new(source_line, line, end, "", CLUES_EXPECTED));
+        }
+        Ok(Self(rule))
     }
 }
 
 impl FromStr for LineRule {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rule: Result<Vec<usize>, _> = s.trim().split(' ').map(|part| part.parse()).collect();
-        match rule {
-            Ok(rule) => Ok(Self(rule)),
-            _ => Err("failed to parse"),
-        }
+        Self::parse_at(s, s, 1, 1)
     }
 }
 
+/// the clues for every row, or every column, of a puzzle
 #[derive(Debug, PartialEq, Clone)]
 pub struct AxisRules(pub Vec<LineRule>);
 
+impl AxisRules {
+    /// parses comma separated line rules from one line of text
+    fn parse_line(source_line: &str, line: usize) -> Result<Vec<LineRule>, ParseError> {
+        Self::parse_at(source_line, source_line, line, 1)
+    }
+
+    /// parses the rules in `text`, which starts at `column` of `source_line`
+    fn parse_at(
+        text: &str,
+        source_line: &str,
+        line: usize,
+        mut column: usize,
+    ) -> Result<Vec<LineRule>, ParseError> {
+        let mut rules = vec![];
+        for text in text.split(',') {
+            rules.push(LineRule::parse_at(text, source_line, line, column)?);
+            column += text.chars().count() + 1;
+        }
+        Ok(rules)
+    }
+}
+
 impl FromStr for AxisRules {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rules_result: Result<Vec<LineRule>, _> =
-            s.trim().split(',').map(LineRule::from_str).collect();
-        match rules_result {
-            Ok(rules) => Ok(Self(rules)),
-            Err(_) => Err("failed to parse"),
-        }
+        Ok(Self(Self::parse_line(s, 1)?))
     }
 }
 
+/// a puzzle, its clues and the optional metadata from its `.pic` file
 #[derive(Debug, PartialEq, Clone)]
 pub struct PicrossGame {
+    /// the clues for each row, top to bottom
     pub rows: AxisRules,
+    /// the clues for each column, left to right
     pub columns: AxisRules,
+    /// the title, author and comments, empty unless read from a `.pic` file
+    pub metadata: PuzzleMetadata,
 }
 
 #[derive(Debug)]
@@ -114,7 +169,7 @@ fn validate_chunks(rule: &LineRule, chunks: Vec<usize>) -> ChunksValidation {
     ChunksValidation::InProgress
 }
 
-fn validate_board(game: &PicrossGame, board: &GameBoard) -> Result<BoardState, &'static str> {
+fn validate_board(game: &PicrossGame, board: &GameBoard) -> Result<BoardState, PicrossError> {
     let mut board_state = BoardState::Complete(board.clone());
     if board.0.len() > game.rows.0.len() {
         return Ok(BoardState::Invalid);
@@ -130,12 +185,88 @@ fn validate_board(game: &PicrossGame, board: &GameBoard) -> Result<BoardState, &
     Ok(board_state)
 }
 
+/// a strategy for solving a `PicrossGame`, reporting each step to an observer
 pub trait PicrossSolver {
-    fn solve(&self) -> Result<PicrossFrame, &'static str>;
+    /// solves the game, printing each step to the terminal
+    fn solve(&self) -> Result<PicrossFrame, PicrossError> {
+        self.solve_with_observer(&mut TerminalPrinter)
+    }
+    /// solves the game, handing each step to `observer`
+    fn solve_with_observer(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<PicrossFrame, PicrossError>;
+    /// solves like `solve_with_observer`, also timing the solve and counting
+    /// its frames
+    fn solve_with_report(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<SolveReport, PicrossError> {
+        report_solve(observer, |observer| {
+            Ok((self.solve_with_observer(observer)?, vec![]))
+        })
+    }
+    /// a solver for `game`
     fn from_game(game: PicrossGame) -> Self;
+    /// replaces the game to solve
     fn set_game(&mut self, game: PicrossGame);
 }
 
+/// a finished solve and what it took
+pub struct SolveReport {
+    /// the last frame of the solve
+    pub frame: PicrossFrame,
+    /// time spent solving, without the time the observer took
+    pub elapsed: Duration,
+    /// counters by name, every solver reports the frames it produced
+    pub statistics: Vec<(&'static str, usize)>,
+}
+
+/// passes frames on to another observer, counting them and the time spent
+/// drawing them, which isn't part of the solve
+struct FrameCounter<'a> {
+    observer: &'a mut dyn FrameObserver,
+    frames: usize,
+    observing: Duration,
+}
+
+impl FrameObserver for FrameCounter<'_> {
+    fn on_frame(&mut self, frame: &PicrossFrame, clear: bool) {
+        self.frames += 1;
+        let start = Instant::now();
+        self.observer.on_frame(frame, clear);
+        self.observing += start.elapsed();
+    }
+    fn on_text(&mut self, text: &str) {
+        self.observer.on_text(text);
+    }
+}
+
+/// runs `solve` and reports it, `solve` returns the solved frame along with
+/// any counters the solver keeps
+pub(crate) fn report_solve(
+    observer: &mut dyn FrameObserver,
+    solve: impl FnOnce(
+        &mut dyn FrameObserver,
+    ) -> Result<(PicrossFrame, Vec<(&'static str, usize)>), PicrossError>,
+) -> Result<SolveReport, PicrossError> {
+    let mut counter = FrameCounter {
+        observer,
+        frames: 0,
+        observing: Duration::ZERO,
+    };
+    let start = Instant::now();
+    let (frame, solver_statistics) = solve(&mut counter)?;
+    let elapsed = start.elapsed().saturating_sub(counter.observing);
+    let mut statistics = vec![("frames", counter.frames)];
+    statistics.extend(solver_statistics);
+    Ok(SolveReport {
+        frame,
+        elapsed,
+        statistics,
+    })
+}
+
 impl Default for PicrossGame {
     fn default() -> Self {
         Self::from_rules("0", "0").unwrap()
@@ -143,109 +274,211 @@ impl Default for PicrossGame {
 }
 
 impl PicrossGame {
+    /// the number of columns
     pub fn width(&self) -> usize {
         self.columns.0.len()
     }
+    /// the number of rows
     pub fn height(&self) -> usize {
         self.rows.0.len()
     }
 
-    pub fn from_rules(row_rules: &str, column_rules: &str) -> Result<Self, String> {
+    /// parses comma separated row and column rules, like "1 1,1,1 1"
+    pub fn from_rules(row_rules: &str, column_rules: &str) -> Result<Self, PicrossError> {
         let rows = AxisRules::from_str(row_rules)?;
         let columns = AxisRules::from_str(column_rules)?;
+        Self::from_axis_rules(rows, columns)
+    }
+
+    /// parses row and column rules on one line separated by a slash, like
+    /// "1 1,1,1 1 / 1 1,1,1 1"
+    pub fn from_clues(clues: &str) -> Result<Self, PicrossError> {
+        let Some((row_rules, column_rules)) = clues.split_once('/') else {
+            let end = clues.chars().count() + 1;
+            let error = ParseError::new(clues, 1, end, "", "\"/\" between row and column clues");
+            return Err(error.into());
+        };
+        let rows = AxisRules::parse_at(row_rules, clues, 1, 1)?;
+        let column = row_rules.chars().count() + 2;
+        let columns = AxisRules::parse_at(column_rules, clues, 1, column)?;
+        Self::from_axis_rules(AxisRules(rows), AxisRules(columns))
+    }
+
+    /// the clues on one line in the form `from_clues` reads
+    pub fn to_clues(&self) -> String {
+        let axis = |rules: &AxisRules| {
+            rules
+                .0
+                .iter()
+                .map(|rule| {
+                    rule.0
+                        .iter()
+                        .map(|clue| clue.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        format!("{} / {}", axis(&self.rows), axis(&self.columns))
+    }
+
+    fn from_axis_rules(rows: AxisRules, columns: AxisRules) -> Result<Self, PicrossError> {
         let row_sum: usize = rows.0.iter().map(|r| r.0.iter().sum::<usize>()).sum();
         let col_sum: usize = columns.0.iter().map(|r| r.0.iter().sum::<usize>()).sum();
         if row_sum != col_sum {
-            eprintln!(
-                "Row Count:{} Row Sum:{}\nCol Count:{} Col Sum:{}",
-                rows.0.len(),
-                row_sum,
-                columns.0.len(),
-                col_sum
-            );
-            return Err(format!(
+            return Err(PicrossError::Validation(format!(
                 "Invalid Rules: Sum of row rules must equal sum of col rules.\nRow sum:{}\nColumn sum:{}"
-            ,row_sum,col_sum));
+            ,row_sum,col_sum)));
         }
-        Ok(Self { rows, columns })
+        Ok(Self {
+            rows,
+            columns,
+            metadata: PuzzleMetadata::default(),
+        })
     }
 
-    pub fn from_text_render(input: &str) -> Result<Self, String> {
-        let rows: Vec<LineRule> = input
-            .lines()
-            .map(|line| {
-                let rule = LineRule::from_render_line(line);
-                rule
-            })
-            .collect();
-        let column_count = input.lines().next().ok_or("no column")?.len();
+    /// rows of "x" for filled tiles and spaces for empty ones, short rows are
+    /// padded with empty tiles
+    pub fn from_text_render(input: &str) -> Result<Self, PicrossError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let rows: Vec<LineRule> = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| LineRule::from_render_line(line, index + 1))
+            .collect::<Result<_, _>>()?;
+        let column_count = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .ok_or_else(|| PicrossError::Validation("no column".to_string()))?;
         let columns: Vec<LineRule> = (0..column_count)
             // collect col_index chars into a string
             .map(|col_index| {
-                input
-                    .lines()
-                    .map(|line| {
-                        return line.chars().nth(col_index).unwrap();
-                    })
+                lines
+                    .iter()
+                    .map(|line| line.chars().nth(col_index).unwrap_or(' '))
                     .collect::<String>()
             })
-            .map(|line| LineRule::from_render_line(&line))
-            .collect();
+            .enumerate()
+            .map(|(index, line)| LineRule::from_render_line(&line, index + 1))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            rows: AxisRules(rows),
+            columns: AxisRules(columns),
+            metadata: PuzzleMetadata::default(),
+        })
+    }
 
+    /// the clues a fully determined board would have, failing on undetermined
+    /// tiles
+    pub fn from_board(board: &GameBoard) -> Result<Self, PicrossError> {
+        let rows: Vec<LineRule> = (0..board.height())
+            .map(|index| board.get_row_chunks(index).map(LineRule))
+            .collect::<Result<_, _>>()?;
+        let columns: Vec<LineRule> = (0..board.width())
+            .map(|index| board.get_column_chunks(index).map(LineRule))
+            .collect::<Result<_, _>>()?;
         Ok(Self {
             rows: AxisRules(rows),
             columns: AxisRules(columns),
+            metadata: PuzzleMetadata::default(),
         })
     }
 
-    pub fn from_rules_file_string(input: &str) -> Result<Self, String> {
-        let mut rules = input
-            .split(DIVIDER)
-            .map(|section| section.trim().lines().collect::<Vec<&str>>().join(","));
-        let row_rules = rules.next().ok_or("invalid file format")?;
-        let col_rules = rules.next().ok_or("invalid file format")?;
-        PicrossGame::from_rules(&row_rules, &col_rules)
+    /// parses a `.pic` puzzle: row rules, a `-----` divider and column rules,
+    /// one line per row or column
+    pub fn from_rules_file_string(input: &str) -> Result<Self, PicrossError> {
+        Self::from_rules_file_lines(input, 0)
+    }
+
+    /// parses a `.pic` puzzle that starts after `line_offset` lines of a
+    /// larger file, so errors point at the right line
+    pub(crate) fn from_rules_file_lines(
+        input: &str,
+        line_offset: usize,
+    ) -> Result<Self, PicrossError> {
+        let mut metadata = PuzzleMetadata::default();
+        let mut rows = vec![];
+        let mut columns = vec![];
+        let mut found_divider = false;
+        let mut last_line = line_offset;
+        for (index, line) in input.lines().enumerate() {
+            let line_number = index + 1 + line_offset;
+            last_line = line_number;
+            let position = match (found_divider, rows.is_empty()) {
+                (false, true) => CommentPosition::Header,
+                (false, false) => CommentPosition::Row(rows.len()),
+                (true, _) => CommentPosition::Column(columns.len()),
+            };
+            if line.trim().is_empty() || metadata.parse_line(line, line_number, position)? {
+                continue;
+            }
+            if line.trim() == DIVIDER {
+                if found_divider {
+                    let column = line.find(DIVIDER).unwrap_or(0) + 1;
+                    let error = ParseError::new(line, line_number, column, DIVIDER, "column clues");
+                    return Err(error.into());
+                }
+                found_divider = true;
+                continue;
+            }
+            let rules = AxisRules::parse_line(line, line_number)?;
+            match found_divider {
+                false => rows.extend(rules),
+                true => columns.extend(rules),
+            }
+        }
+        let missing = match (found_divider, rows.is_empty(), columns.is_empty()) {
+            (_, true, _) => Some("row clues"),
+            (false, _, _) => Some("a \"-----\" line followed by column clues"),
+            (true, _, true) => Some("column clues"),
+            _ => None,
+        };
+        if let Some(expected) = missing {
+            let error = ParseError::new("", last_line + 1, 1, "", expected);
+            return Err(error.into());
+        }
+        let mut game = PicrossGame::from_axis_rules(AxisRules(rows), AxisRules(columns))?;
+        game.metadata = metadata;
+        Ok(game)
     }
 
+    /// the puzzle as a `.pic` file, with its `#` comments where they were read
     pub fn to_rules_file_string(&self) -> String {
-        let row_string: String = self
-            .rows
-            .0
-            .iter()
-            .map(|rule| {
-                rule.0
-                    .iter()
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>()
-                    .join(" ")
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-        let col_string: String = self
-            .columns
-            .0
-            .iter()
-            .map(|rule| {
-                rule.0
-                    .iter()
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>()
-                    .join(" ")
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-        format!("{row_string}\n{DIVIDER}\n{col_string}")
+        let axis = |rules: &AxisRules, position: fn(usize) -> CommentPosition| {
+            let mut text = String::new();
+            for (index, rule) in rules.0.iter().enumerate() {
+                text.push_str(&self.metadata.comment_lines(position(index)));
+                let clues: Vec<String> = rule.0.iter().map(|x| x.to_string()).collect();
+                text.push_str(&clues.join(" "));
+                text.push('\n');
+            }
+            text + &self.metadata.comment_lines(position(rules.0.len()))
+        };
+        let header = self.metadata.to_header_string();
+        let rows = axis(&self.rows, CommentPosition::Row);
+        let columns = axis(&self.columns, CommentPosition::Column);
+        let text = format!("{header}{rows}{DIVIDER}\n{columns}");
+        text.strip_suffix('\n').unwrap_or(&text).to_string()
+    }
+
+    /// whether both puzzles have the same clues, whatever their metadata
+    pub fn same_clues(&self, other: &Self) -> bool {
+        self.rows == other.rows && self.columns == other.columns
     }
 
+    /// line solves every row against `reference_board`, or an empty board
     pub fn get_partial_board_from_rows(
         &self,
         reference_board: Option<GameBoard>,
-    ) -> Result<GameBoard, &'static str> {
+    ) -> Result<GameBoard, PicrossError> {
         let board = match reference_board {
             Some(board) => board,
             None => GameBoard::new(self.width(), self.height()),
         };
-        let rows: Result<Vec<GameBoardRow>, &'static str> = self
+        let rows: Result<Vec<GameBoardRow>, PicrossError> = self
             .rows
             .0
             .iter()
@@ -258,10 +491,11 @@ impl PicrossGame {
         rows.map(GameBoard)
     }
 
-    fn get_partial_board_from_columns(
+    /// line solves every column against `reference_board`, or an empty board
+    pub fn get_partial_board_from_columns(
         &self,
         reference_board: Option<GameBoard>,
-    ) -> Result<GameBoard, &'static str> {
+    ) -> Result<GameBoard, PicrossError> {
         let current_board = reference_board.unwrap_or(GameBoard::new(self.width(), self.height()));
         let mut board_flipped = GameBoard::new(self.height(), self.width());
         for (y, row) in current_board.0.iter().enumerate() {
@@ -270,7 +504,7 @@ impl PicrossGame {
             }
         }
 
-        let columns: Result<Vec<GameBoardRow>, &'static str> = self
+        let columns: Result<Vec<GameBoardRow>, PicrossError> = self
             .columns
             .0
             .iter()
@@ -295,6 +529,7 @@ mod tests {
     use picross_solver_v1::PicrossSolverV1;
     use picross_solver_v2::PicrossSolverV2;
     use picross_solver_v3::PicrossSolverV3;
+    use picross_solver_v4::PicrossSolverV4;
 
     use crate::{
         game_board::{GameBoardRow, TileState::*},
@@ -359,6 +594,24 @@ mod tests {
         let mut solver = PicrossSolverV3(PicrossGame::from_rules("0", "0").unwrap());
         run_solver_tests(&mut solver);
     }
+    #[test]
+    fn test_solver_v4() {
+        let mut solver = PicrossSolverV4(PicrossGame::from_rules("0", "0").unwrap());
+        run_solver_tests(&mut solver);
+
+        // line solving alone cannot decide anything in this puzzle
+        let ambiguous_game = PicrossGame::from_rules("1,1", "1,1").unwrap();
+        solver.set_game(ambiguous_game.clone());
+        let solved_frame = solver.solve().unwrap();
+        assert_eq!(solved_frame.game_state, GameState::Complete);
+        assert_eq!(
+            PicrossGame::from_board(&solved_frame.board).unwrap(),
+            ambiguous_game
+        );
+
+        solver.set_game(PicrossGame::from_rules("2,0", "0,2").unwrap());
+        assert_eq!(solver.solve().unwrap().game_state, GameState::Invalid);
+    }
 
     #[test]
     fn test_to_rules_string() {
@@ -390,6 +643,89 @@ mod tests {
         assert_eq!(input, output);
     }
 
+    #[test]
+    fn test_rules_string_metadata() {
+        let input = "\
+# from the starter pack
+title: Corners
+author: Jane Doe
+license: CC0
+source: https://example.com/corners
+difficulty: 1
+comment: every corner is filled
+1 1
+0
+# the last row matches the first
+1 1
+-----
+1 1
+0
+1 1";
+        let game = PicrossGame::from_rules_file_string(input).unwrap();
+        assert_eq!(game.rows, AxisRules::from_str("1 1,0,1 1").unwrap());
+        assert_eq!(game.metadata.title.as_deref(), Some("Corners"));
+        assert_eq!(game.metadata.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(game.metadata.license.as_deref(), Some("CC0"));
+        assert_eq!(game.metadata.difficulty.as_deref(), Some("1"));
+        assert_eq!(
+            game.metadata.comments,
+            vec![
+                (CommentPosition::Header, "from the starter pack".to_string()),
+                (
+                    CommentPosition::Row(2),
+                    "the last row matches the first".to_string()
+                )
+            ]
+        );
+
+        // comments stay where they were
+        let output = game.to_rules_file_string();
+        assert!(output.starts_with("# from the starter pack\ntitle: Corners\n"));
+        assert!(output.contains("\n0\n# the last row matches the first\n1 1\n-----\n"));
+        assert_eq!(PicrossGame::from_rules_file_string(&output).unwrap(), game);
+        let plain = PicrossGame::from_rules("1 1,0,1 1", "1 1,0,1 1").unwrap();
+        assert!(plain != game && plain.same_clues(&game));
+        let input = "1\n# before the divider\n-----\n# first column\n1\n# at the end";
+        let game = PicrossGame::from_rules_file_string(input).unwrap();
+        assert_eq!(game.to_rules_file_string(), input);
+        assert!(PicrossGame::from_rules_file_string("size: 1\n1\n-----\n1").is_err());
+    }
+
+    #[test]
+    fn test_rules_file_parse_errors() {
+        let error = PicrossGame::from_rules_file_string("1\n1 x\n-----\n1\n1")
+            .unwrap_err()
+            .to_string();
+        assert!(matches!(
+            PicrossGame::from_rules_file_string("1 x\n-----\n1"),
+            Err(PicrossError::Parse(ParseError {
+                line: 1,
+                column: 3,
+                ..
+            }))
+        ));
+        assert_eq!(
+            error,
+            "line 2, column 3: found \"x\", expected clue numbers separated by spaces\n2 | 1 x\n  |   ^"
+        );
+        let error = PicrossGame::from_rules_file_string("1\n1")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("line 3, column 1: found end of line, expected a \"-----\" line"));
+        let error = PicrossGame::from_rules_file_string("1\n-----\n1\n-----\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("line 4, column 1: found \"-----\", expected column clues"));
+        let error = PicrossGame::from_rules_file_string("1,,1\n-----\n1,1")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("line 1, column 3: found end of line"));
+        let error = PicrossGame::from_rules_file_lines("# c\nsize: 1\n", 10)
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("line 12, column 1: found \"size\""));
+    }
+
     #[test]
     fn test_row_column_rules_from_string() {
         let res = AxisRules::from_str("1,0,1 2");
@@ -402,6 +738,27 @@ mod tests {
         assert_eq!(res.unwrap(), expected);
     }
 
+    #[test]
+    fn test_from_clues() {
+        let game = PicrossGame::from_clues("1 1,1,1 1 / 1 1,1,1 1").unwrap();
+        assert_eq!(
+            game,
+            PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap()
+        );
+        let Err(PicrossError::Parse(error)) = PicrossGame::from_clues("1,1 / 1,x") else {
+            panic!("x is not a clue");
+        };
+        assert_eq!((error.column, error.token.as_str()), (9, "x"));
+        let Err(PicrossError::Parse(error)) = PicrossGame::from_clues("1,1") else {
+            panic!("the column clues are missing");
+        };
+        assert_eq!(error.column, 4);
+
+        let game = PicrossGame::from_rules("2 1,0,1", "1 1,1,0,1").unwrap();
+        assert_eq!(game.to_clues(), "2 1,0,1 / 1 1,1,0,1");
+        assert_eq!(PicrossGame::from_clues(&game.to_clues()).unwrap(), game);
+    }
+
     #[test]
     fn test_validate_chunks() {
         let rule = LineRule(vec![3]);
@@ -432,9 +789,19 @@ mod tests {
     #[test]
     fn test_line_rule_from_render_text() {
         let line = "XX   XXXX   XX";
-        let rule = LineRule::from_render_line(line);
+        let rule = LineRule::from_render_line(line, 1).unwrap();
         let expected = LineRule(vec![2, 4, 2]);
         assert_eq!(rule, expected);
+
+        assert_eq!(
+            LineRule::from_render_line("   ", 1).unwrap(),
+            LineRule(vec![0])
+        );
+        let error = LineRule::from_render_line("xx#x", 3).unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.token.as_str()),
+            (3, 3, "#")
+        );
     }
 
     #[test]
@@ -450,7 +817,23 @@ xxx"
         let expected = PicrossGame {
             rows: AxisRules::from_str("1 1,1,0,3,3").unwrap(),
             columns: AxisRules::from_str("1 2,1 2,1 2").unwrap(),
+            metadata: PuzzleMetadata::default(),
         };
         pretty_assertions::assert_eq!(game, expected);
     }
+
+    #[test]
+    fn test_from_board() {
+        let board = GameBoard(vec![
+            GameBoardRow(vec![Filled, Empty, Filled]),
+            GameBoardRow(vec![Empty, Empty, Empty]),
+            GameBoardRow(vec![Filled, Filled, Empty]),
+        ]);
+        let game = PicrossGame::from_board(&board).unwrap();
+        let expected = PicrossGame::from_rules("1 1,0,2", "1 1,1,1").unwrap();
+        assert_eq!(game, expected);
+
+        let undetermined = GameBoard::new(2, 2);
+        assert!(PicrossGame::from_board(&undetermined).is_err());
+    }
 }
@@ -0,0 +1,160 @@
+use std::fmt::Write;
+
+use crate::error::ParseError;
+
+/// where a `#` comment line was in a `.pic` file, so writing the puzzle back
+/// puts it in the same place
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CommentPosition {
+    /// before any clues, written ahead of the `key: value` lines
+    Header,
+    /// before the row clue with this index, or before the divider when it is
+    /// the number of rows
+    Row(usize),
+    /// before the column clue with this index, or at the end of the file when
+    /// it is the number of columns
+    Column(usize),
+}
+
+/// optional information carried in the header of a `.pic` file as
+/// `key: value` lines, plus any `#` comment lines found in the file
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PuzzleMetadata {
+    /// the `title:` line
+    pub title: Option<String>,
+    /// the `author:` line
+    pub author: Option<String>,
+    /// the `license:` line
+    pub license: Option<String>,
+    /// the `source:` line, where the puzzle comes from
+    pub source: Option<String>,
+    /// the `difficulty:` line, as written in the file
+    pub difficulty: Option<String>,
+    /// free-form text, one `comment:` line per line of text
+    pub comment: Option<String>,
+    /// `#` comment lines, without the leading `#`, and where they were
+    pub comments: Vec<(CommentPosition, String)>,
+}
+
+impl PuzzleMetadata {
+    /// reads a `#` comment found at `position` or a `key: value` header line,
+    /// returning false for lines that belong to the rules
+    pub fn parse_line(
+        &mut self,
+        source_line: &str,
+        line_number: usize,
+        position: CommentPosition,
+    ) -> Result<bool, ParseError> {
+        let line = source_line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.strip_prefix(' ').unwrap_or(comment);
+            self.comments.push((position, comment.to_string()));
+            return Ok(true);
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            return Ok(false);
+        };
+        let value = value.trim().to_string();
+        let field = match key.trim().to_lowercase().as_str() {
+            "title" => &mut self.title,
+            "author" => &mut self.author,
+            "license" => &mut self.license,
+            "source" | "url" => &mut self.source,
+            "difficulty" => &mut self.difficulty,
+            "comment" => {
+                self.comment = Some(match self.comment.take() {
+                    Some(comment) => format!("{}\n{}", comment, value),
+                    None => value,
+                });
+                return Ok(true);
+            }
+            _ => {
+                let key = key.trim();
+                let column = source_line
+                    .chars()
+                    .take_while(|char| char.is_whitespace())
+                    .count();
+                return Err(ParseError::new(
+                    source_line,
+                    line_number,
+                    column + 1,
+                    key,
+                    "one of title, author, license, source, difficulty or comment",
+                ));
+            }
+        };
+        *field = Some(value);
+        Ok(true)
+    }
+
+    /// the `#` comment lines that go at `position`
+    pub fn comment_lines(&self, position: CommentPosition) -> String {
+        let mut lines = String::new();
+        for (_, comment) in self.comments.iter().filter(|(at, _)| *at == position) {
+            let _ = writeln!(lines, "# {}", comment);
+        }
+        lines
+    }
+
+    /// the header comments and `key: value` lines, each ending in a newline
+    pub fn to_header_string(&self) -> String {
+        let mut header = self.comment_lines(CommentPosition::Header);
+        let fields = [
+            ("title", &self.title),
+            ("author", &self.author),
+            ("license", &self.license),
+            ("source", &self.source),
+            ("difficulty", &self.difficulty),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                let _ = writeln!(header, "{}: {}", key, value);
+            }
+        }
+        for line in self.comment.iter().flat_map(|comment| comment.lines()) {
+            let _ = writeln!(header, "comment: {}", line);
+        }
+        header
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let mut metadata = PuzzleMetadata::default();
+        let mut parse = |line: &str| metadata.parse_line(line, 1, CommentPosition::Header);
+        assert_eq!(parse("Title: Heart"), Ok(true));
+        assert_eq!(parse("url: https://example.com/heart"), Ok(true));
+        assert_eq!(parse("comment: first"), Ok(true));
+        assert_eq!(parse("comment: second"), Ok(true));
+        assert_eq!(parse("#  indented"), Ok(true));
+        assert_eq!(parse("1 2"), Ok(false));
+        assert!(parse("colour: red").is_err());
+        assert_eq!(
+            metadata.parse_line("# later", 2, CommentPosition::Row(1)),
+            Ok(true)
+        );
+
+        assert_eq!(metadata.title.as_deref(), Some("Heart"));
+        assert_eq!(
+            metadata.source.as_deref(),
+            Some("https://example.com/heart")
+        );
+        assert_eq!(metadata.comment.as_deref(), Some("first\nsecond"));
+        assert_eq!(
+            metadata.comments,
+            vec![
+                (CommentPosition::Header, " indented".to_string()),
+                (CommentPosition::Row(1), "later".to_string())
+            ]
+        );
+        assert_eq!(metadata.comment_lines(CommentPosition::Row(1)), "# later\n");
+        assert_eq!(
+            metadata.to_header_string(),
+            "#  indented\ntitle: Heart\nsource: https://example.com/heart\ncomment: first\ncomment: second\n"
+        );
+    }
+}
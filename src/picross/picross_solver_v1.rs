@@ -1,25 +1,31 @@
 use std::slice::Iter;
 
 use crate::{
+    error::PicrossError,
     game_board::{GameBoard, GameBoardRow},
     iterators::PicrossLineIter,
     picross::{validate_board, BoardState, LineRule},
-    render::{GameState, PicrossFrame},
+    render::{FrameObserver, GameState, PicrossFrame},
 };
 
 use super::{PicrossGame, PicrossSolver};
 
+/// brute force: tries every arrangement of each row in turn, backtracking
+/// when the columns can no longer fit
 pub struct PicrossSolverV1(pub PicrossGame);
 
 impl PicrossSolver for PicrossSolverV1 {
     fn set_game(&mut self, game: PicrossGame) {
         self.0 = game;
     }
-    fn solve(&self) -> Result<PicrossFrame, &'static str> {
+    fn solve_with_observer(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<PicrossFrame, PicrossError> {
         let initial_board = GameBoard::new(self.0.width(), self.0.height());
         let frame =
             PicrossFrame::new(self.0.clone(), initial_board.clone(), GameState::InProgress)?;
-        frame.print(true);
+        observer.on_frame(&frame, true);
         let width = self.0.columns.0.len();
 
         struct StackEntry<'a> {
@@ -51,16 +57,12 @@ impl PicrossSolver for PicrossSolverV1 {
                 }
             }
             let frame = PicrossFrame::new(self.0.clone(), render_board, GameState::InProgress)?;
-            frame.print(false);
+            observer.on_frame(&frame, false);
 
             match validate_board(&self.0, &board)? {
                 BoardState::Invalid => (),
                 BoardState::Complete(complete_board) => {
-                    return Ok(PicrossFrame::new(
-                        self.0.clone(),
-                        complete_board,
-                        GameState::Complete,
-                    )?)
+                    return PicrossFrame::new(self.0.clone(), complete_board, GameState::Complete)
                 }
                 BoardState::InProgress => {
                     let next_row_layout_iter = row_iter
@@ -91,7 +93,7 @@ impl PicrossSolver for PicrossSolverV1 {
         }
         let failed_frame = PicrossFrame::new(
             self.0.clone(),
-            last_board.ok_or("no last board")?.clone(),
+            last_board.unwrap_or(initial_board),
             GameState::Invalid,
         )?;
         Ok(failed_frame)
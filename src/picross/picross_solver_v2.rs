@@ -1,24 +1,30 @@
 use crate::{
+    error::PicrossError,
     game_board::{GameBoard, TileState},
-    render::{GameState, PicrossFrame},
+    render::{FrameObserver, GameState, PicrossFrame},
 };
 
 use super::{PicrossGame, PicrossSolver};
 
+/// line solving: sweeps all rows then all columns until a sweep changes
+/// nothing
 pub struct PicrossSolverV2(pub PicrossGame);
 
 impl PicrossSolver for PicrossSolverV2 {
-    fn solve(&self) -> Result<PicrossFrame, &'static str> {
+    fn solve_with_observer(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<PicrossFrame, PicrossError> {
         let initial_board = GameBoard::new(self.0.width(), self.0.height());
         let frame =
             PicrossFrame::new(self.0.clone(), initial_board.clone(), GameState::InProgress)?;
-        frame.print(true);
+        observer.on_frame(&frame, true);
         let mut current_board = self
             .0
             .get_partial_board_from_columns(Some(self.0.get_partial_board_from_rows(None)?))?;
         let frame =
             PicrossFrame::new(self.0.clone(), current_board.clone(), GameState::InProgress)?;
-        frame.print(false);
+        observer.on_frame(&frame, false);
         loop {
             let new_board = self.0.get_partial_board_from_columns(Some(
                 self.0
@@ -26,7 +32,7 @@ impl PicrossSolver for PicrossSolverV2 {
             ))?;
             let frame =
                 PicrossFrame::new(self.0.clone(), new_board.clone(), GameState::InProgress)?;
-            frame.print(false);
+            observer.on_frame(&frame, false);
             if current_board == new_board {
                 if new_board.0.iter().all(|row| {
                     row.0
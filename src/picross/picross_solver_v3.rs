@@ -1,20 +1,26 @@
 use std::collections::VecDeque;
 
 use crate::{
+    error::PicrossError,
     game_board::{GameBoard, GameBoardRow, TileState},
     iterators::PicrossLineIter,
-    render::{GameState, PicrossFrame},
+    render::{FrameObserver, GameState, PicrossFrame},
 };
 
 use super::{PicrossGame, PicrossSolver};
 
+/// line solving with a queue, only revisiting the lines crossing a tile
+/// that changed
 pub struct PicrossSolverV3(pub PicrossGame);
 
 impl PicrossSolver for PicrossSolverV3 {
-    fn solve(&self) -> Result<PicrossFrame, &'static str> {
+    fn solve_with_observer(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<PicrossFrame, PicrossError> {
         let mut board = GameBoard::new(self.0.width(), self.0.height());
         let frame = PicrossFrame::new(self.0.clone(), board.clone(), GameState::InProgress)?;
-        frame.print(true);
+        observer.on_frame(&frame, true);
         #[derive(Debug, PartialEq)]
         enum ToCheck {
             Row,
@@ -26,17 +32,18 @@ impl PicrossSolver for PicrossSolverV3 {
         // populate the rows initially
         while let Some((board_axis, index)) = queue.pop_front() {
             let frame = PicrossFrame::new(self.0.clone(), board.clone(), GameState::InProgress)?;
-            frame.print(false);
+            observer.on_frame(&frame, false);
             match board_axis {
                 ToCheck::Row => {
                     let row_index = index;
                     // maybe rethink this...
-                    let rules = &self.0.rows.0.get(index).ok_or("failed to get row rules")?;
+                    let rules = &self.0.rows.0.get(index).ok_or_else(|| {
+                        PicrossError::Validation("failed to get row rules".to_string())
+                    })?;
                     let mut line_iter = PicrossLineIter::new(&rules.0, self.0.width());
-                    let board_row = board
-                        .0
-                        .get_mut(row_index)
-                        .ok_or("failed to get board row")?;
+                    let board_row = board.0.get_mut(row_index).ok_or_else(|| {
+                        PicrossError::Validation("failed to get board row".to_string())
+                    })?;
                     let solved = line_iter.get_partially_solved_line(Some(board_row))?;
 
                     for (col_index, tile) in board_row.0.iter_mut().enumerate() {
@@ -61,20 +68,16 @@ impl PicrossSolver for PicrossSolverV3 {
                 }
                 ToCheck::Column => {
                     let col_index = index;
-                    let rules = self
-                        .0
-                        .columns
-                        .0
-                        .get(col_index)
-                        .ok_or("failed to get col rules")?;
+                    let rules = self.0.columns.0.get(col_index).ok_or_else(|| {
+                        PicrossError::Validation("failed to get col rules".to_string())
+                    })?;
                     let mut line_iter = PicrossLineIter::new(&rules.0, self.0.height());
-                    let board_col = GameBoardRow(board.get_column(col_index));
+                    let board_col = GameBoardRow(board.get_column(col_index)?);
                     let solved_col = line_iter.get_partially_solved_line(Some(&board_col))?;
                     for (row_index, tile) in board_col.0.iter().enumerate() {
-                        let solved_tile = &solved_col
-                            .0
-                            .get(row_index)
-                            .ok_or("failed to get solved column tile")?;
+                        let solved_tile = &solved_col.0.get(row_index).ok_or_else(|| {
+                            PicrossError::Validation("failed to get solved column tile".to_string())
+                        })?;
                         match (&tile, solved_tile) {
                             (TileState::Undetermined, TileState::Filled) => {
                                 if !queue.contains(&(ToCheck::Row, row_index)) {
@@ -0,0 +1,91 @@
+use crate::{
+    error::PicrossError,
+    game_board::GameBoard,
+    render::{FrameObserver, GameState, PicrossFrame},
+    sat::{
+        cdcl::{CdclSolver, SatResult, SatStatistics},
+        cnf::{board_from_model, cell_variable, encode_game},
+    },
+};
+
+use super::{report_solve, PicrossGame, PicrossSolver, SolveReport};
+
+/// solves the puzzle as a SAT problem with the built in CDCL solver, so it
+/// also finishes puzzles that line solving alone leaves undetermined
+pub struct PicrossSolverV4(pub PicrossGame);
+
+impl PicrossSolverV4 {
+    /// solves like `solve_with_observer`, also returning the SAT solver's
+    /// counters
+    pub fn solve_with_statistics(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<(PicrossFrame, SatStatistics), PicrossError> {
+        let (width, height) = (self.0.width(), self.0.height());
+        let board = GameBoard::new(width, height);
+        let frame = PicrossFrame::new(self.0.clone(), board.clone(), GameState::InProgress)?;
+        observer.on_frame(&frame, true);
+
+        let mut solver = CdclSolver::new(&encode_game(&self.0));
+        // after each restart show the tiles proven so far
+        let mut show_proven_tiles = |solver: &CdclSolver| {
+            let model: Vec<i32> = (0..height)
+                .flat_map(|y| (0..width).map(move |x| cell_variable(width, x, y)))
+                .filter_map(|variable| {
+                    let value = solver.value(variable as usize)?;
+                    Some(if value { variable } else { -variable })
+                })
+                .collect();
+            let board = board_from_model(width, height, &model);
+            if let Ok(frame) = PicrossFrame::new(self.0.clone(), board, GameState::InProgress) {
+                observer.on_frame(&frame, false);
+            }
+        };
+        let frame = match solver.solve_with_progress(&mut show_proven_tiles) {
+            SatResult::Satisfiable(model) => {
+                let board = board_from_model(width, height, &model);
+                PicrossFrame::new(self.0.clone(), board, GameState::Complete)?
+            }
+            SatResult::Unsatisfiable => {
+                PicrossFrame::new(self.0.clone(), board, GameState::Invalid)?
+            }
+        };
+        Ok((frame, solver.statistics))
+    }
+}
+
+impl PicrossSolver for PicrossSolverV4 {
+    fn solve_with_observer(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<PicrossFrame, PicrossError> {
+        self.solve_with_statistics(observer).map(|(frame, _)| frame)
+    }
+
+    fn solve_with_report(
+        &self,
+        observer: &mut dyn FrameObserver,
+    ) -> Result<SolveReport, PicrossError> {
+        report_solve(observer, |observer| {
+            let (frame, statistics) = self.solve_with_statistics(observer)?;
+            Ok((
+                frame,
+                vec![
+                    ("decisions", statistics.decisions),
+                    ("propagations", statistics.propagations),
+                    ("conflicts", statistics.conflicts),
+                    ("restarts", statistics.restarts),
+                    ("learned_clauses", statistics.learned_clauses),
+                ],
+            ))
+        })
+    }
+
+    fn from_game(game: PicrossGame) -> Self {
+        Self(game)
+    }
+
+    fn set_game(&mut self, game: PicrossGame) {
+        self.0 = game;
+    }
+}
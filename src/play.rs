@@ -0,0 +1,1336 @@
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use picross_solver::{
+    broken_lines, check_uniqueness, search_path, ClueLayout, GameBoard, ImageOptions, Input,
+    InputFormat, PicrossError, PicrossGame, TileState, Uniqueness, Viewport,
+};
+use session::Session;
+use termion::{
+    clear, color, cursor,
+    event::{Event, Key, MouseButton, MouseEvent},
+    input::{MouseTerminal, TermRead},
+    raw::IntoRawMode,
+    screen::IntoAlternateScreen,
+    style,
+};
+
+mod session;
+
+const TITLE_SCREEN: &str = include_str!("../assets/title_screen.txt");
+const HELP: &str =
+    "arrows/hjkl move  space fill  x mark  c clear  F/X/C drag  u/r undo/redo  ? check  S save  q quit";
+const DRAG_HELP: &str = "moving paints a straight run, press space or F/X/C again to stop";
+/// lines above the column clues, the header and a blank line
+const HEADER_LINES: usize = 2;
+/// lines below the board, a blank line and the help
+const FOOTER_LINES: usize = 2;
+/// picker rows shown at once
+const PICKER_HEIGHT: usize = 15;
+
+/// a puzzle offered by the picker
+pub struct PuzzleChoice {
+    pub name: String,
+    pub game: PicrossGame,
+}
+
+/// every puzzle in the search path directories, listing each puzzle of a
+/// collection as `file#name`, files that don't parse are left out
+pub fn find_puzzles() -> Vec<PuzzleChoice> {
+    let mut choices = vec![];
+    for directory in search_path() {
+        let Ok(entries) = fs::read_dir(&directory) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let Some(format) = InputFormat::from_path(&path) else {
+                continue;
+            };
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let input = Input {
+                path: Some(path),
+                format,
+            };
+            let Ok(collection) = input.load(&ImageOptions::default()) else {
+                continue;
+            };
+            let single = collection.len() == 1;
+            for entry in collection.0 {
+                let name = match single {
+                    true => stem.clone(),
+                    false => format!("{}#{}", stem, entry.name),
+                };
+                choices.push(PuzzleChoice {
+                    name,
+                    game: entry.game,
+                });
+            }
+        }
+    }
+    choices
+}
+
+/// how much play helps with mistakes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Assist {
+    Off,
+    /// wrong tiles are flagged when the board is checked
+    Check,
+    /// wrong fills are flagged and marked as empty as soon as they're made
+    Instant,
+}
+
+impl FromStr for Assist {
+    type Err = PicrossError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Assist::Off),
+            "check" => Ok(Assist::Check),
+            "instant" => Ok(Assist::Instant),
+            _ => Err(PicrossError::Validation(format!(
+                "invalid assist mode \"{}\"\n Available modes are: off, check, instant",
+                s
+            ))),
+        }
+    }
+}
+
+/// Picross DS adds 2 minutes for the first mistake, 4 for the second and 8
+/// for every one after that
+fn mistake_penalty(mistake: usize) -> Duration {
+    Duration::from_secs(120 << (mistake.clamp(1, 3) - 1))
+}
+
+/// what a fill, mark or clear does to a tile
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Paint {
+    /// toggles between filled and unplayed
+    Fill,
+    /// toggles between marked empty and unplayed
+    Mark,
+    Clear,
+}
+
+impl Paint {
+    fn target(self, current: &TileState) -> TileState {
+        match (self, current) {
+            (Paint::Fill, TileState::Filled) | (Paint::Mark, TileState::Empty) => {
+                TileState::Undetermined
+            }
+            (Paint::Fill, _) => TileState::Filled,
+            (Paint::Mark, _) => TileState::Empty,
+            (Paint::Clear, _) => TileState::Undetermined,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Move(isize, isize),
+    Paint(Paint),
+    /// starts painting a straight run from the cursor as it moves, or stops
+    Drag(Paint),
+    /// moves the cursor to a tile, extending a drag
+    Select(usize, usize),
+    EndDrag,
+    /// crosses a clue number off, or back on
+    CrossOff(Clue),
+    Undo,
+    Redo,
+    /// highlights the lines that can no longer fit their clues, and the
+    /// wrong tiles when the solution is known
+    Check,
+}
+
+fn action_for_key(key: Key) -> Option<Action> {
+    match key {
+        Key::Left | Key::Char('h') | Key::Char('a') => Some(Action::Move(-1, 0)),
+        Key::Right | Key::Char('l') | Key::Char('d') => Some(Action::Move(1, 0)),
+        Key::Up | Key::Char('k') | Key::Char('w') => Some(Action::Move(0, -1)),
+        Key::Down | Key::Char('j') | Key::Char('s') => Some(Action::Move(0, 1)),
+        Key::Char(' ') | Key::Char('f') | Key::Char('\n') => Some(Action::Paint(Paint::Fill)),
+        Key::Char('x') | Key::Char('m') => Some(Action::Paint(Paint::Mark)),
+        Key::Char('c') | Key::Backspace | Key::Delete => Some(Action::Paint(Paint::Clear)),
+        Key::Char('F') => Some(Action::Drag(Paint::Fill)),
+        Key::Char('X') | Key::Char('M') => Some(Action::Drag(Paint::Mark)),
+        Key::Char('C') => Some(Action::Drag(Paint::Clear)),
+        Key::Char('u') | Key::Ctrl('z') => Some(Action::Undo),
+        Key::Char('r') | Key::Ctrl('y') => Some(Action::Redo),
+        Key::Char('?') => Some(Action::Check),
+        _ => None,
+    }
+}
+
+/// a clue number, by its line and its place in that line's clue
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Clue {
+    Row(usize, usize),
+    Column(usize, usize),
+}
+
+/// what `PlayState::render` draws at a spot on the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    Tile(usize, usize),
+    Clue(Clue),
+}
+
+/// one tile changed by an edit
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileChange {
+    pub x: usize,
+    pub y: usize,
+    pub from: TileState,
+    pub to: TileState,
+}
+
+/// a run being painted from `anchor`, it changes the tiles holding what the
+/// anchor held before, like dragging the stylus in Picross DS
+struct Drag {
+    anchor: (usize, usize),
+    from: TileState,
+    to: TileState,
+    /// the tiles painted so far
+    painted: Vec<TileChange>,
+}
+
+/// the lengths of the filled runs in a line, `[0]` when there are none, the
+/// same form `LineRule` uses
+fn filled_runs<'a>(tiles: impl Iterator<Item = &'a TileState>) -> Vec<usize> {
+    let mut runs = vec![];
+    let mut count = 0;
+    for tile in tiles {
+        match tile {
+            TileState::Filled => count += 1,
+            _ if count > 0 => {
+                runs.push(count);
+                count = 0;
+            }
+            _ => (),
+        }
+    }
+    if count > 0 || runs.is_empty() {
+        runs.push(count);
+    }
+    runs
+}
+
+/// a puzzle being played, unplayed tiles are `TileState::Undetermined` and
+/// tiles marked as empty are `TileState::Empty`
+pub struct PlayState {
+    pub game: PicrossGame,
+    pub board: GameBoard,
+    /// (x, y) of the selected tile
+    pub cursor: (usize, usize),
+    started: Instant,
+    /// time played in earlier sessions
+    previous: Duration,
+    /// how long solving took, once the board matches every clue
+    solved_after: Option<Duration>,
+    /// edits that can be undone, oldest first
+    history: Vec<Vec<TileChange>>,
+    /// undone edits, the next one to redo last
+    undone: Vec<Vec<TileChange>>,
+    drag: Option<Drag>,
+    assist: Assist,
+    /// the only solution, known once assisting
+    solution: Option<GameBoard>,
+    pub mistakes: usize,
+    /// time added for mistakes since the session started
+    penalty: Duration,
+    /// wrong tiles with the state they were flagged in, shown until they change
+    flagged: Vec<(usize, usize, TileState)>,
+    /// the rows and the columns the last check found broken
+    broken: (Vec<usize>, Vec<usize>),
+    /// shown instead of the help line until the next key
+    pub status: Option<String>,
+    /// clue numbers the player has crossed off
+    crossed: Vec<Clue>,
+    /// the part of the board shown, see `fit`
+    viewport: Viewport,
+}
+
+impl PlayState {
+    pub fn new(game: PicrossGame) -> Self {
+        let board = GameBoard::new(game.width(), game.height());
+        Self {
+            game,
+            board,
+            cursor: (0, 0),
+            started: Instant::now(),
+            previous: Duration::ZERO,
+            solved_after: None,
+            history: vec![],
+            undone: vec![],
+            drag: None,
+            assist: Assist::Off,
+            solution: None,
+            mistakes: 0,
+            penalty: Duration::ZERO,
+            flagged: vec![],
+            broken: (vec![], vec![]),
+            status: None,
+            crossed: vec![],
+            viewport: Viewport::unlimited(),
+        }
+    }
+
+    /// solves the puzzle to check the player's tiles against, which needs
+    /// the solution to be unique
+    pub fn set_assist(&mut self, assist: Assist) -> Result<(), PicrossError> {
+        self.solution = match assist {
+            Assist::Off => None,
+            _ => match check_uniqueness(&self.game) {
+                Uniqueness::Unique(solution) => Some(solution),
+                Uniqueness::Multiple(..) => {
+                    return Err(PicrossError::Validation(
+                        "assisted play needs a puzzle with a single solution".to_string(),
+                    ))
+                }
+                Uniqueness::Unsolvable => {
+                    return Err(PicrossError::Contradiction(
+                        "the puzzle has no solution".to_string(),
+                    ))
+                }
+            },
+        };
+        self.assist = assist;
+        Ok(())
+    }
+
+    fn add_mistake(&mut self, x: usize, y: usize) {
+        self.mistakes += 1;
+        self.penalty += mistake_penalty(self.mistakes);
+        self.flagged.push((x, y, self.board[(x, y)].clone()));
+    }
+
+    /// the tiles that differ from the solution, leaving out unplayed ones
+    fn wrong_tiles(&self) -> Vec<(usize, usize)> {
+        let Some(solution) = &self.solution else {
+            return vec![];
+        };
+        let mut wrong = vec![];
+        for (y, (row, solution_row)) in self.board.rows().zip(solution.rows()).enumerate() {
+            for (x, (tile, solution_tile)) in row.iter().zip(solution_row).enumerate() {
+                if *tile != TileState::Undetermined && tile != solution_tile {
+                    wrong.push((x, y));
+                }
+            }
+        }
+        wrong
+    }
+
+    fn check(&mut self) {
+        self.broken = broken_lines(&self.game, &self.board).unwrap_or_default();
+        let mut found = 0;
+        for (x, y) in self.wrong_tiles() {
+            if !self.is_flagged(x, y) {
+                self.add_mistake(x, y);
+                found += 1;
+            }
+        }
+        let lines = self.broken.0.len() + self.broken.1.len();
+        self.status = Some(match (lines, found) {
+            (0, 0) => "every row and column can still fit its clues".to_string(),
+            (lines, 0) => format!("{} lines can no longer fit their clues", lines),
+            (lines, found) => format!(
+                "{} lines can no longer fit their clues, {} wrong tiles",
+                lines, found
+            ),
+        });
+    }
+
+    fn is_flagged(&self, x: usize, y: usize) -> bool {
+        self.flagged
+            .iter()
+            .any(|(flagged_x, flagged_y, _)| (*flagged_x, *flagged_y) == (x, y))
+    }
+
+    /// carries on from a saved session
+    pub fn resume(session: Session) -> Self {
+        let mut state = Self::new(session.game);
+        state.board = session.board;
+        state.previous = session.elapsed;
+        state.history = session.history;
+        state.mistakes = session.mistakes;
+        let board = &state.board;
+        state.flagged = session
+            .flagged
+            .into_iter()
+            .map(|(x, y)| (x, y, board[(x, y)].clone()))
+            .collect();
+        if state.is_solved() {
+            state.solved_after = Some(state.previous);
+        }
+        state
+    }
+
+    pub fn session(&self, name: &str) -> Session {
+        Session {
+            puzzle: name.to_string(),
+            game: self.game.clone(),
+            board: self.board.clone(),
+            elapsed: self.elapsed(),
+            mistakes: self.mistakes,
+            flagged: self.flagged.iter().map(|(x, y, _)| (*x, *y)).collect(),
+            history: self.history.clone(),
+        }
+    }
+
+    fn apply(&mut self, action: Action) {
+        let before = self.board.clone();
+        self.status = None;
+        match action {
+            Action::Move(dx, dy) => {
+                let (x, y) = self.cursor;
+                let x = x.saturating_add_signed(dx).min(self.game.width() - 1);
+                let y = y.saturating_add_signed(dy).min(self.game.height() - 1);
+                self.cursor = (x, y);
+                self.drag_to(self.cursor);
+            }
+            Action::Select(x, y) => {
+                self.cursor = (x, y);
+                self.drag_to(self.cursor);
+            }
+            Action::EndDrag => self.end_drag(),
+            Action::CrossOff(clue) => match self.crossed.iter().position(|&other| other == clue) {
+                Some(index) => {
+                    self.crossed.remove(index);
+                }
+                None => self.crossed.push(clue),
+            },
+            // painting while dragging just ends the drag, so the last tile
+            // of the run isn't toggled back
+            Action::Paint(_) | Action::Drag(_) if self.drag.is_some() => self.end_drag(),
+            Action::Paint(paint) => {
+                self.start_drag(paint);
+                self.end_drag();
+            }
+            Action::Drag(paint) => self.start_drag(paint),
+            Action::Undo => {
+                self.end_drag();
+                if let Some(edit) = self.history.pop() {
+                    for change in edit.iter().rev() {
+                        self.board[(change.x, change.y)] = change.from.clone();
+                    }
+                    self.undone.push(edit);
+                }
+            }
+            Action::Redo => {
+                self.end_drag();
+                if let Some(edit) = self.undone.pop() {
+                    for change in edit.iter() {
+                        self.board[(change.x, change.y)] = change.to.clone();
+                    }
+                    self.history.push(edit);
+                }
+            }
+            Action::Check => {
+                self.end_drag();
+                self.check();
+            }
+        }
+        if self.board != before {
+            self.broken = (vec![], vec![]);
+            let board = &self.board;
+            self.flagged
+                .retain(|(x, y, state)| board[(*x, *y)] == *state);
+        }
+        if self.solved_after.is_none() && self.is_solved() {
+            self.end_drag();
+            self.solved_after = Some(self.elapsed());
+        }
+    }
+
+    fn start_drag(&mut self, paint: Paint) {
+        let (x, y) = self.cursor;
+        let from = self.board[(x, y)].clone();
+        self.drag = Some(Drag {
+            anchor: self.cursor,
+            to: paint.target(&from),
+            from,
+            painted: vec![],
+        });
+        self.drag_to(self.cursor);
+    }
+
+    /// repaints the run from the anchor towards `(x, y)`, along whichever
+    /// axis is further
+    fn drag_to(&mut self, (x, y): (usize, usize)) {
+        let Some(mut drag) = self.drag.take() else {
+            return;
+        };
+        for change in drag.painted.drain(..).rev() {
+            self.board[(change.x, change.y)] = change.from;
+        }
+        let (anchor_x, anchor_y) = drag.anchor;
+        // painted outwards from the anchor, so a mistake stops the run there
+        let along = |from: usize, to: usize| -> Vec<usize> {
+            match to >= from {
+                true => (from..=to).collect(),
+                false => (to..=from).rev().collect(),
+            }
+        };
+        let run: Vec<(usize, usize)> = match x.abs_diff(anchor_x) >= y.abs_diff(anchor_y) {
+            true => along(anchor_x, x)
+                .into_iter()
+                .map(|x| (x, anchor_y))
+                .collect(),
+            false => along(anchor_y, y)
+                .into_iter()
+                .map(|y| (anchor_x, y))
+                .collect(),
+        };
+        for (x, y) in run {
+            if self.board[(x, y)] != drag.from || drag.from == drag.to {
+                continue;
+            }
+            let wrong = self.assist == Assist::Instant
+                && drag.to == TileState::Filled
+                && self.solution.as_ref().map(|solution| &solution[(x, y)])
+                    != Some(&TileState::Filled);
+            let to = match wrong {
+                true => TileState::Empty,
+                false => drag.to.clone(),
+            };
+            drag.painted.push(TileChange {
+                x,
+                y,
+                from: drag.from.clone(),
+                to: to.clone(),
+            });
+            self.board[(x, y)] = to;
+            if wrong {
+                self.add_mistake(x, y);
+                self.drag = Some(drag);
+                self.end_drag();
+                return;
+            }
+        }
+        self.drag = Some(drag);
+    }
+
+    /// keeps the painted run as one edit
+    fn end_drag(&mut self) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        if !drag.painted.is_empty() {
+            self.history.push(drag.painted);
+            self.undone.clear();
+        }
+    }
+
+    pub fn row_satisfied(&self, y: usize) -> bool {
+        let row = (0..self.board.width()).map(|x| &self.board[(x, y)]);
+        filled_runs(row) == self.game.rows.0[y].0
+    }
+
+    pub fn column_satisfied(&self, x: usize) -> bool {
+        let column = self.board.rows().map(|row| &row[x]);
+        filled_runs(column) == self.game.columns.0[x].0
+    }
+
+    /// true once the filled tiles match every clue, unplayed tiles count as empty
+    pub fn is_solved(&self) -> bool {
+        (0..self.game.height()).all(|y| self.row_satisfied(y))
+            && (0..self.game.width()).all(|x| self.column_satisfied(x))
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.solved_after
+            .unwrap_or_else(|| self.previous + self.started.elapsed() + self.penalty)
+    }
+
+    /// the width of the row clues, the height of the column clues and the
+    /// width of the tiles, which place the board below the header in `render`
+    /// the way a `PicrossFrame` is drawn
+    fn layout(&self) -> ClueLayout {
+        ClueLayout::new(&self.game)
+    }
+
+    /// the columns and rows of the board that `render` shows
+    fn visible(&self) -> (Range<usize>, Range<usize>) {
+        let layout = self.layout();
+        let size = (self.game.width(), self.game.height());
+        let margins = (layout.row_padding, HEADER_LINES + layout.clue_height);
+        self.viewport.visible(size, margins, layout.tile_width)
+    }
+
+    /// fits the board into a `width` by `height` terminal, scrolled to keep
+    /// the cursor in view with the clues pinned above and to the left
+    pub fn fit(&mut self, width: usize, height: usize) {
+        let layout = self.layout();
+        let size = (self.game.width(), self.game.height());
+        self.viewport.width = width;
+        self.viewport.height = height.saturating_sub(FOOTER_LINES);
+        let margins = (layout.row_padding, HEADER_LINES + layout.clue_height);
+        self.viewport
+            .scroll_to(size, margins, layout.tile_width, self.cursor);
+    }
+
+    /// what `render` draws at the zero based `column` and `line`
+    fn target_at(&self, column: usize, line: usize) -> Option<Target> {
+        let layout = self.layout();
+        let (columns, rows) = self.visible();
+        let line = line.checked_sub(HEADER_LINES)?;
+        if column >= layout.row_padding {
+            let x = columns.start + (column - layout.row_padding) / layout.tile_width;
+            if !columns.contains(&x) {
+                return None;
+            }
+            if line < layout.clue_height {
+                let index = layout.column_clue_index(&self.game.columns.0[x], line)?;
+                return Some(Target::Clue(Clue::Column(x, index)));
+            }
+            let y = rows.start + line - layout.clue_height;
+            return rows.contains(&y).then_some(Target::Tile(x, y));
+        }
+        let y = rows.start + line.checked_sub(layout.clue_height)?;
+        if !rows.contains(&y) {
+            return None;
+        }
+        layout
+            .row_clue_spans(&self.game.rows.0[y])
+            .iter()
+            .position(|span| span.contains(&column))
+            .map(|index| Target::Clue(Clue::Row(y, index)))
+    }
+
+    /// the tile nearest to the zero based `column` and `line`, so drags can
+    /// leave the board, or reach one tile past the edge shown to scroll
+    fn nearest_tile(&self, column: usize, line: usize) -> (usize, usize) {
+        let layout = self.layout();
+        let (columns, rows) = self.visible();
+        let x = match column.checked_sub(layout.row_padding) {
+            Some(offset) => (columns.start + offset / layout.tile_width).min(columns.end),
+            None => columns.start.saturating_sub(1),
+        };
+        let y = match line.checked_sub(HEADER_LINES + layout.clue_height) {
+            Some(offset) => (rows.start + offset).min(rows.end),
+            None => rows.start.saturating_sub(1),
+        };
+        (x.min(self.game.width() - 1), y.min(self.game.height() - 1))
+    }
+
+    /// left clicks fill, right clicks mark and middle clicks clear, dragging
+    /// paints a straight run, clicking a clue number crosses it off
+    fn actions_for_mouse(&self, event: MouseEvent) -> Vec<Action> {
+        match event {
+            MouseEvent::Press(button, column, line) => {
+                let paint = match button {
+                    MouseButton::Left => Paint::Fill,
+                    MouseButton::Right => Paint::Mark,
+                    MouseButton::Middle => Paint::Clear,
+                    _ => return vec![],
+                };
+                let Some((column, line)) = mouse_position(column, line) else {
+                    return vec![];
+                };
+                match self.target_at(column, line) {
+                    Some(Target::Tile(x, y)) => {
+                        vec![Action::EndDrag, Action::Select(x, y), Action::Drag(paint)]
+                    }
+                    Some(Target::Clue(clue)) => vec![Action::CrossOff(clue)],
+                    None => vec![],
+                }
+            }
+            MouseEvent::Hold(column, line) if self.drag.is_some() => {
+                let Some((column, line)) = mouse_position(column, line) else {
+                    return vec![];
+                };
+                let (x, y) = self.nearest_tile(column, line);
+                vec![Action::Select(x, y)]
+            }
+            MouseEvent::Hold(..) => vec![],
+            MouseEvent::Release(..) => vec![Action::EndDrag],
+        }
+    }
+
+    /// a clue number right aligned to `width`, struck through once crossed
+    /// off
+    fn clue_text(&self, clue: Clue, value: usize, width: usize) -> String {
+        let text = value.to_string();
+        let padding = " ".repeat(width.saturating_sub(text.len()));
+        match self.crossed.contains(&clue) {
+            true => format!(
+                "{}{}{}{}{}{}",
+                padding,
+                style::CrossedOut,
+                style::Faint,
+                text,
+                style::NoFaint,
+                style::NoCrossedOut
+            ),
+            false => padding + &text,
+        }
+    }
+
+    fn render(&self, name: &str) -> Vec<String> {
+        let (cursor_x, cursor_y) = self.cursor;
+        let layout = self.layout();
+        let (columns, rows) = self.visible();
+        let clue_style = |satisfied: bool, highlighted: bool, broken: bool| match (
+            broken,
+            satisfied,
+            highlighted,
+        ) {
+            (true, _, _) => color::Fg(color::LightRed).to_string(),
+            (false, true, _) => color::Fg(color::LightBlack).to_string(),
+            (false, false, true) => color::Fg(color::LightYellow).to_string(),
+            (false, false, false) => String::new(),
+        };
+
+        let elapsed = self.elapsed().as_secs();
+        let mut header = format!("{}  {:02}:{:02}", name, elapsed / 60, elapsed % 60);
+        if self.assist != Assist::Off {
+            header.push_str(&format!("  mistakes: {}", self.mistakes));
+        }
+        let size = (self.game.width(), self.game.height());
+        if let Some(position) = Viewport::describe(&columns, &rows, size) {
+            header.push_str(&format!("  {}", position));
+        }
+        let width = self.viewport.width;
+        let mut lines = vec![truncate(&header, width), String::new()];
+        let digits = layout.tile_width - 1;
+        for line in 0..layout.clue_height {
+            let mut text = " ".repeat(layout.row_padding);
+            for x in columns.clone() {
+                let rule = &self.game.columns.0[x];
+                text.push_str(&clue_style(
+                    self.column_satisfied(x),
+                    x == cursor_x,
+                    self.broken.1.contains(&x),
+                ));
+                match layout.column_clue_index(rule, line) {
+                    Some(index) => {
+                        text.push_str(&self.clue_text(
+                            Clue::Column(x, index),
+                            rule.0[index],
+                            digits,
+                        ));
+                        text.push(' ');
+                    }
+                    None => text.push_str(&" ".repeat(layout.tile_width)),
+                }
+                text.push_str(style::Reset.as_ref());
+            }
+            lines.push(text);
+        }
+        for y in rows {
+            let mut text = clue_style(
+                self.row_satisfied(y),
+                y == cursor_y,
+                self.broken.0.contains(&y),
+            );
+            let rule = &self.game.rows.0[y];
+            let mut end = 0;
+            for (index, span) in layout.row_clue_spans(rule).into_iter().enumerate() {
+                text.push_str(&" ".repeat(span.start - end));
+                let clue = self.clue_text(Clue::Row(y, index), rule.0[index], layout.row_digits);
+                text.push_str(&clue);
+                end = span.end;
+            }
+            text.push_str(&" ".repeat(layout.row_padding - end));
+            text.push_str(style::Reset.as_ref());
+            let blank = " ".repeat(layout.tile_width - 1);
+            for x in columns.clone() {
+                let glyph = match self.board[(x, y)] {
+                    TileState::Filled => "█".repeat(layout.tile_width),
+                    TileState::Empty => blank.clone() + "x",
+                    TileState::Undetermined => blank.clone() + "·",
+                };
+                if (x, y) == self.cursor {
+                    text.push_str(&format!(
+                        "{}{}",
+                        color::Bg(color::Blue),
+                        color::Fg(color::LightCyan)
+                    ));
+                } else if x == cursor_x || y == cursor_y {
+                    text.push_str(&color::Bg(color::AnsiValue::grayscale(5)).to_string());
+                }
+                if self.is_flagged(x, y) {
+                    text.push_str(&color::Fg(color::LightRed).to_string());
+                }
+                text.push_str(&glyph);
+                text.push_str(style::Reset.as_ref());
+            }
+            lines.push(text);
+        }
+        lines.push(String::new());
+        let footer = match (self.solved_after, &self.status) {
+            (Some(_), _) => format!(
+                "Solved in {:02}:{:02}! Press any key",
+                elapsed / 60,
+                elapsed % 60
+            ),
+            (None, Some(status)) => status.clone(),
+            (None, None) if self.drag.is_some() => DRAG_HELP.to_string(),
+            (None, None) => HELP.to_string(),
+        };
+        lines.push(truncate(&footer, width));
+        lines
+    }
+}
+
+/// termion's one based mouse position as a zero based one, `None` where the
+/// terminal can't report the position and termion gives 0
+fn mouse_position(column: u16, line: u16) -> Option<(usize, usize)> {
+    let column = usize::from(column).checked_sub(1)?;
+    Some((column, usize::from(line).checked_sub(1)?))
+}
+
+/// the first `width` characters of `text`, which has no escape sequences, so
+/// it doesn't wrap onto another line
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+fn terminal_error(error: io::Error) -> PicrossError {
+    PicrossError::io("terminal", error)
+}
+
+fn draw(screen: &mut impl Write, lines: &[String]) -> Result<(), PicrossError> {
+    write!(
+        screen,
+        "{}{}{}",
+        clear::All,
+        cursor::Goto(1, 1),
+        lines.join("\r\n")
+    )
+    .and_then(|_| screen.flush())
+    .map_err(terminal_error)
+}
+
+/// fits the board to the terminal, which may have been resized
+fn fit_terminal(state: &mut PlayState) {
+    let terminal = Viewport::terminal();
+    state.fit(terminal.width, terminal.height);
+}
+
+type Events = dyn Iterator<Item = io::Result<Event>>;
+
+/// where a puzzle's session is saved unless another file is given, named
+/// after the puzzle in the current directory
+fn default_session_path(name: &str) -> PathBuf {
+    let stem: String = name
+        .chars()
+        .map(|char| match char.is_alphanumeric() || char == '-' {
+            true => char,
+            false => '_',
+        })
+        .collect();
+    PathBuf::from(format!("{}.session", stem))
+}
+
+/// plays until the puzzle is solved or the player quits, resuming the
+/// session at `session_path` if there is one, once saved the session is
+/// saved again on the way out
+fn play_puzzle(
+    screen: &mut impl Write,
+    events: &mut Events,
+    name: &str,
+    game: PicrossGame,
+    session_path: &Path,
+    assist: Assist,
+) -> Result<(), PicrossError> {
+    if game.width() == 0 || game.height() == 0 {
+        return Err(PicrossError::Validation(
+            "the puzzle has no tiles to play".to_string(),
+        ));
+    }
+    let mut saved = session_path.is_file();
+    let mut state = match saved {
+        true => {
+            let session = Session::load(session_path)?;
+            if !session.game.same_clues(&game) {
+                return Err(PicrossError::Validation(format!(
+                    "\"{}\" is a session of another puzzle, \"{}\"",
+                    session_path.display(),
+                    session.puzzle
+                )));
+            }
+            let mut state = PlayState::resume(session);
+            state.status = Some(format!("resumed from {}", session_path.display()));
+            state
+        }
+        false => PlayState::new(game),
+    };
+    state.set_assist(assist)?;
+    fit_terminal(&mut state);
+    draw(screen, &state.render(name))?;
+    for event in events {
+        let event = event.map_err(terminal_error)?;
+        match (&event, state.solved_after) {
+            // mouse events after the win, like releasing the last drag, don't
+            // leave the board before it's seen
+            (Event::Key(_), Some(_)) | (Event::Key(Key::Char('q') | Key::Esc), None) => break,
+            (_, Some(_)) => continue,
+            _ => (),
+        }
+        match event {
+            Event::Key(Key::Char('S') | Key::Ctrl('s')) => {
+                state.end_drag();
+                state.session(name).save(session_path)?;
+                saved = true;
+                state.status = Some(format!("saved to {}", session_path.display()));
+            }
+            Event::Key(key) => {
+                if let Some(action) = action_for_key(key) {
+                    state.apply(action);
+                }
+            }
+            Event::Mouse(mouse) => {
+                for action in state.actions_for_mouse(mouse) {
+                    state.apply(action);
+                }
+            }
+            Event::Unsupported(_) => continue,
+        }
+        fit_terminal(&mut state);
+        draw(screen, &state.render(name))?;
+    }
+    if saved {
+        state.end_drag();
+        state.session(name).save(session_path)?;
+    }
+    Ok(())
+}
+
+fn pick_puzzle<'a>(
+    screen: &mut impl Write,
+    events: &mut Events,
+    choices: &'a [PuzzleChoice],
+    selected: &mut usize,
+) -> Result<Option<&'a PuzzleChoice>, PicrossError> {
+    if choices.is_empty() {
+        return Err(PicrossError::Validation(
+            "no puzzles found, add some to ./puzzles or set PICROSS_PATH".to_string(),
+        ));
+    }
+    loop {
+        let first = selected.saturating_sub(PICKER_HEIGHT - 1);
+        let mut lines: Vec<String> = TITLE_SCREEN.lines().map(String::from).collect();
+        lines.push(String::new());
+        for (index, choice) in choices.iter().enumerate().skip(first).take(PICKER_HEIGHT) {
+            let size = format!("{}x{}", choice.game.width(), choice.game.height());
+            let text = format!("{:<30} {:>7}", choice.name, size);
+            match index == *selected {
+                true => lines.push(format!("> {}{}{}", style::Invert, text, style::Reset)),
+                false => lines.push(format!("  {}", text)),
+            }
+        }
+        lines.push(String::new());
+        lines.push("up/down choose  enter play  q quit".to_string());
+        draw(screen, &lines)?;
+
+        let Some(event) = events.next() else {
+            return Ok(None);
+        };
+        match event.map_err(terminal_error)? {
+            Event::Key(Key::Up | Key::Char('k') | Key::Char('w'))
+            | Event::Mouse(MouseEvent::Press(MouseButton::WheelUp, ..)) => {
+                *selected = selected.saturating_sub(1)
+            }
+            Event::Key(Key::Down | Key::Char('j') | Key::Char('s'))
+            | Event::Mouse(MouseEvent::Press(MouseButton::WheelDown, ..)) => {
+                *selected = (*selected + 1).min(choices.len() - 1)
+            }
+            // clicking a puzzle plays it
+            Event::Mouse(MouseEvent::Press(MouseButton::Left, column, line)) => {
+                let top = TITLE_SCREEN.lines().count() + 1;
+                let index = mouse_position(column, line)
+                    .and_then(|(_, line)| line.checked_sub(top))
+                    .map(|row| first + row)
+                    .filter(|index| *index < choices.len().min(first + PICKER_HEIGHT));
+                if let Some(index) = index {
+                    *selected = index;
+                    return Ok(Some(&choices[index]));
+                }
+            }
+            Event::Key(Key::Char('\n') | Key::Char(' ')) => return Ok(Some(&choices[*selected])),
+            Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')) => return Ok(None),
+            _ => (),
+        }
+    }
+}
+
+/// plays `puzzle` in the terminal, or the puzzle saved in `session`, or
+/// without either shows the title screen and lets the player pick puzzles
+/// from the search path until they quit
+pub fn play(
+    puzzle: Option<(String, PicrossGame)>,
+    session: Option<PathBuf>,
+    assist: Assist,
+) -> Result<(), PicrossError> {
+    if !io::stdout().is_terminal() || !io::stdin().is_terminal() {
+        return Err(PicrossError::Validation(
+            "play needs an interactive terminal".to_string(),
+        ));
+    }
+    let puzzle = match (puzzle, &session) {
+        (None, Some(path)) => {
+            let session = Session::load(path)?;
+            Some((session.puzzle, session.game))
+        }
+        (puzzle, _) => puzzle,
+    };
+    let raw = io::stdout().into_raw_mode().map_err(terminal_error)?;
+    let mut screen = MouseTerminal::from(raw.into_alternate_screen().map_err(terminal_error)?);
+    let mut events = io::stdin().events();
+    write!(screen, "{}", cursor::Hide).map_err(terminal_error)?;
+
+    let result = match puzzle {
+        Some((name, game)) => {
+            let path = session.unwrap_or_else(|| default_session_path(&name));
+            play_puzzle(&mut screen, &mut events, &name, game, &path, assist)
+        }
+        None => {
+            let choices = find_puzzles();
+            let mut selected = 0;
+            loop {
+                match pick_puzzle(&mut screen, &mut events, &choices, &mut selected) {
+                    Ok(Some(choice)) => {
+                        let game = choice.game.clone();
+                        let path = default_session_path(&choice.name);
+                        if let Err(error) =
+                            play_puzzle(&mut screen, &mut events, &choice.name, game, &path, assist)
+                        {
+                            break Err(error);
+                        }
+                    }
+                    Ok(None) => break Ok(()),
+                    Err(error) => break Err(error),
+                }
+            }
+        }
+    };
+    write!(screen, "{}", cursor::Show).map_err(terminal_error)?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filled_runs() {
+        use TileState::*;
+        assert_eq!(
+            filled_runs([Filled, Undetermined, Filled, Filled].iter()),
+            vec![1, 2]
+        );
+        assert_eq!(filled_runs([Empty, Undetermined].iter()), vec![0]);
+    }
+
+    #[test]
+    fn test_play_state() {
+        let game = PicrossGame::from_rules("1,3,1", "1,3,1").unwrap();
+        let mut state = PlayState::new(game);
+        state.apply(Action::Move(-1, -1));
+        assert_eq!(state.cursor, (0, 0));
+        state.apply(Action::Paint(Paint::Mark));
+        assert_eq!(state.board[(0, 0)], TileState::Empty);
+        state.apply(Action::Paint(Paint::Fill));
+        assert_eq!(state.board[(0, 0)], TileState::Filled);
+        state.apply(Action::Paint(Paint::Fill));
+        assert_eq!(state.board[(0, 0)], TileState::Undetermined);
+
+        for (x, y) in [(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)] {
+            state.cursor = (x, y);
+            assert!(!state.is_solved());
+            state.apply(Action::Paint(Paint::Fill));
+        }
+        assert!(state.row_satisfied(0) && state.column_satisfied(2));
+        assert!(state.is_solved());
+        assert!(state.solved_after.is_some());
+        state.apply(Action::Move(5, 5));
+        assert_eq!(state.cursor, (2, 2));
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let game = PicrossGame::from_rules("1,3,1", "1,3,1").unwrap();
+        let mut state = PlayState::new(game);
+        state.apply(Action::Paint(Paint::Fill));
+        state.apply(Action::Move(1, 0));
+        state.apply(Action::Paint(Paint::Mark));
+        state.apply(Action::Undo);
+        assert_eq!(state.board[(1, 0)], TileState::Undetermined);
+        state.apply(Action::Undo);
+        state.apply(Action::Undo);
+        assert_eq!(state.board, GameBoard::new(3, 3));
+        state.apply(Action::Redo);
+        assert_eq!(state.board[(0, 0)], TileState::Filled);
+
+        // a new edit drops the edits left to redo
+        state.apply(Action::Paint(Paint::Fill));
+        state.apply(Action::Redo);
+        assert_eq!(state.board[(1, 0)], TileState::Filled);
+        assert_eq!(state.history.len(), 2);
+    }
+
+    #[test]
+    fn test_drag() {
+        let game = PicrossGame::from_rules("3,1,1", "3,1,1").unwrap();
+        let mut state = PlayState::new(game);
+        state.cursor = (1, 0);
+        state.apply(Action::Paint(Paint::Mark));
+        state.cursor = (0, 0);
+        state.apply(Action::Drag(Paint::Fill));
+        state.apply(Action::Move(1, 0));
+        state.apply(Action::Move(1, 0));
+        // marked tiles are left alone, and the run follows the longer axis
+        assert_eq!(state.board.render(), "██  ██\n??????\n??????");
+        state.apply(Action::Move(0, 1));
+        assert_eq!(state.board.render(), "██  ██\n??????\n??????");
+        state.apply(Action::Move(-2, 1));
+        assert_eq!(state.board.render(), "██  ??\n██????\n██????");
+        state.apply(Action::Paint(Paint::Fill));
+        assert!(state.drag.is_none());
+        assert_eq!(state.board[(0, 2)], TileState::Filled);
+
+        // the whole run is undone at once
+        state.apply(Action::Undo);
+        assert_eq!(state.board[(0, 0)], TileState::Undetermined);
+        assert_eq!(state.board[(1, 0)], TileState::Empty);
+    }
+
+    #[test]
+    fn test_resume() {
+        let game = PicrossGame::from_rules("1,3,1", "1,3,1").unwrap();
+        let mut state = PlayState::new(game);
+        state.apply(Action::Drag(Paint::Fill));
+        state.apply(Action::Move(0, 1));
+        state.apply(Action::Paint(Paint::Fill));
+        let mut session = state.session("cross");
+        session.elapsed = Duration::from_secs(90);
+
+        let mut state = PlayState::resume(session);
+        assert!(state.elapsed() >= Duration::from_secs(90));
+        assert_eq!(state.board[(0, 1)], TileState::Filled);
+        state.apply(Action::Undo);
+        assert_eq!(state.board, GameBoard::new(3, 3));
+    }
+
+    #[test]
+    fn test_mistake_penalty() {
+        assert_eq!(mistake_penalty(1), Duration::from_secs(120));
+        assert_eq!(mistake_penalty(2), Duration::from_secs(240));
+        assert_eq!(mistake_penalty(3), Duration::from_secs(480));
+        assert_eq!(mistake_penalty(7), Duration::from_secs(480));
+        assert!(Assist::from_str("sometimes").is_err());
+    }
+
+    #[test]
+    fn test_instant_assist() {
+        let game = PicrossGame::from_rules("1,3,1", "1,3,1").unwrap();
+        let mut state = PlayState::new(game);
+        state.set_assist(Assist::Instant).unwrap();
+        state.cursor = (1, 0);
+        state.apply(Action::Paint(Paint::Fill));
+        assert_eq!(state.mistakes, 0);
+
+        // the run stops at the wrong tile, which is marked instead
+        state.cursor = (1, 0);
+        state.apply(Action::Drag(Paint::Fill));
+        state.apply(Action::Move(0, 1));
+        state.cursor = (0, 1);
+        state.apply(Action::Paint(Paint::Fill));
+        state.cursor = (0, 1);
+        state.apply(Action::Drag(Paint::Fill));
+        state.apply(Action::Move(0, -1));
+        assert!(state.drag.is_none());
+        assert_eq!(state.board[(0, 0)], TileState::Empty);
+        assert_eq!(state.mistakes, 1);
+        assert!(state.is_flagged(0, 0));
+        assert!(state.elapsed() >= Duration::from_secs(120));
+
+        let game = PicrossGame::from_rules("1,1", "1,1").unwrap();
+        let mut state = PlayState::new(game);
+        assert!(state.set_assist(Assist::Check).is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let game = PicrossGame::from_rules("1,3,1", "1,3,1").unwrap();
+        let mut state = PlayState::new(game);
+        state.apply(Action::Drag(Paint::Fill));
+        state.apply(Action::Move(1, 0));
+        state.apply(Action::Paint(Paint::Fill));
+        state.apply(Action::Check);
+        assert_eq!(state.broken, (vec![0], vec![]));
+        assert_eq!(state.mistakes, 0);
+
+        state.set_assist(Assist::Check).unwrap();
+        state.apply(Action::Check);
+        assert_eq!(state.mistakes, 1);
+        assert!(state.is_flagged(0, 0) && !state.is_flagged(1, 0));
+        // checking again doesn't count the same tile twice
+        state.apply(Action::Check);
+        assert_eq!(state.mistakes, 1);
+        // nor does checking after resuming a saved session
+        let text = state.session("cross").to_session_string();
+        let mut resumed = PlayState::resume(Session::from_session_string(&text).unwrap());
+        resumed.set_assist(Assist::Check).unwrap();
+        resumed.apply(Action::Check);
+        assert_eq!(resumed.mistakes, 1);
+        assert!(resumed.is_flagged(0, 0));
+        assert!(resumed.elapsed() < Duration::from_secs(240));
+
+        // changing the board clears the highlights
+        state.cursor = (0, 0);
+        state.apply(Action::Paint(Paint::Clear));
+        assert_eq!(state.broken, (vec![], vec![]));
+        assert!(!state.is_flagged(0, 0));
+    }
+
+    #[test]
+    fn test_target_at() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let state = PlayState::new(game);
+        // three columns of row clues, then two lines of column clues under
+        // the header
+        let layout = state.layout();
+        assert_eq!((layout.row_padding, layout.clue_height), (3, 2));
+        assert_eq!(state.target_at(0, 4), Some(Target::Clue(Clue::Row(0, 0))));
+        assert_eq!(state.target_at(1, 4), None);
+        assert_eq!(state.target_at(2, 4), Some(Target::Clue(Clue::Row(0, 1))));
+        assert_eq!(state.target_at(3, 4), Some(Target::Tile(0, 0)));
+        assert_eq!(state.target_at(8, 6), Some(Target::Tile(2, 2)));
+        assert_eq!(state.target_at(9, 4), None);
+        assert_eq!(state.target_at(4, 7), None);
+        assert_eq!(
+            state.target_at(4, 2),
+            Some(Target::Clue(Clue::Column(0, 0)))
+        );
+        assert_eq!(state.target_at(5, 2), None);
+        assert_eq!(
+            state.target_at(6, 3),
+            Some(Target::Clue(Clue::Column(1, 0)))
+        );
+        assert_eq!(state.target_at(0, 1), None);
+    }
+
+    #[test]
+    fn test_multi_digit_clues() {
+        // columns of 100, 10 and 12 tiles
+        let board = GameBoard::from_rows(
+            (0..100)
+                .map(|y| {
+                    [true, y < 10, y < 12]
+                        .map(|filled| match filled {
+                            true => TileState::Filled,
+                            false => TileState::Empty,
+                        })
+                        .to_vec()
+                })
+                .collect(),
+        );
+        let state = PlayState::new(PicrossGame::from_board(&board).unwrap());
+        // the columns are as wide as the 100 and a space, like a frame draws
+        // them, and the row clues line up the same way
+        assert_eq!(state.layout().tile_width, 4);
+        let lines = state.render("tall");
+        assert!(lines[2].contains("100 "));
+        assert!(lines[2].contains(" 10 "));
+        assert!(lines[2].contains(" 12 "));
+        assert_eq!(state.target_at(6, 3), Some(Target::Tile(0, 0)));
+        assert_eq!(state.target_at(7, 3), Some(Target::Tile(1, 0)));
+        assert_eq!(
+            state.target_at(12, 2),
+            Some(Target::Clue(Clue::Column(2, 0)))
+        );
+        assert_eq!(state.target_at(0, 13), Some(Target::Clue(Clue::Row(10, 0))));
+        assert_eq!(state.target_at(2, 13), Some(Target::Clue(Clue::Row(10, 1))));
+        assert_eq!(state.target_at(1, 3), None);
+        assert_eq!(state.target_at(2, 3), Some(Target::Clue(Clue::Row(0, 0))));
+    }
+
+    #[test]
+    fn test_mouse() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let mut state = PlayState::new(game);
+        let send = |state: &mut PlayState, event: MouseEvent| {
+            for action in state.actions_for_mouse(event) {
+                state.apply(action);
+            }
+        };
+        send(&mut state, MouseEvent::Press(MouseButton::Left, 4, 5));
+        // dragging past the board paints up to its edge
+        send(&mut state, MouseEvent::Hold(20, 5));
+        send(&mut state, MouseEvent::Release(20, 5));
+        assert_eq!(state.board.render(), "██████\n??????\n??????");
+        assert_eq!(state.history.len(), 1);
+
+        send(&mut state, MouseEvent::Press(MouseButton::Right, 6, 5));
+        send(&mut state, MouseEvent::Release(6, 5));
+        assert_eq!(state.board[(1, 0)], TileState::Empty);
+        // holding without a drag paints nothing
+        send(&mut state, MouseEvent::Hold(4, 7));
+        assert_eq!(state.board[(0, 2)], TileState::Undetermined);
+
+        // positions past what the terminal reports come as 0 and are ignored
+        assert!(state
+            .actions_for_mouse(MouseEvent::Press(MouseButton::Left, 0, 5))
+            .is_empty());
+        state.apply(Action::Drag(Paint::Fill));
+        assert!(state.actions_for_mouse(MouseEvent::Hold(0, 0)).is_empty());
+        state.apply(Action::EndDrag);
+
+        send(&mut state, MouseEvent::Press(MouseButton::Left, 3, 5));
+        assert_eq!(state.crossed, vec![Clue::Row(0, 1)]);
+        send(&mut state, MouseEvent::Press(MouseButton::Left, 3, 5));
+        assert!(state.crossed.is_empty());
+    }
+
+    #[test]
+    fn test_fit() {
+        let game = PicrossGame::from_rules("1,1,1,1,1,1", "1,1,1,1,1,1").unwrap();
+        let mut state = PlayState::new(game);
+        // a clue column, then room for 3 tiles, and 3 rows between the
+        // header, clues and footer
+        state.fit(7, 8);
+        assert_eq!(state.visible(), (0..3, 0..3));
+        state.cursor = (4, 5);
+        state.fit(7, 8);
+        assert_eq!(state.visible(), (2..5, 3..6));
+        assert_eq!(state.target_at(1, 3), Some(Target::Tile(2, 3)));
+        assert_eq!(
+            state.target_at(1, 2),
+            Some(Target::Clue(Clue::Column(2, 0)))
+        );
+        assert_eq!(state.target_at(0, 3), Some(Target::Clue(Clue::Row(3, 0))));
+        assert_eq!(state.nearest_tile(20, 20), (5, 5));
+
+        // the header and help are cut to the terminal's width
+        let lines = state.render("six");
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0].chars().count(), 7);
+        assert_eq!(lines[7].chars().count(), 7);
+        state.fit(60, 8);
+        assert!(state.render("six")[0].contains("columns 1-6 of 6, rows 4-6 of 6"));
+    }
+
+    #[test]
+    fn test_default_session_path() {
+        assert_eq!(
+            default_session_path("starter#cross 2"),
+            PathBuf::from("starter_cross_2.session")
+        );
+    }
+}
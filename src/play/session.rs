@@ -0,0 +1,319 @@
+use std::{path::Path, time::Duration};
+
+use picross_solver::{GameBoard, ParseError, PicrossError, PicrossGame, TileState};
+
+use super::TileChange;
+
+const MOVES_EXPECTED: &str = "changes like \"3,4,.#\": x, y, then the tile before and after";
+
+/// a saved game, enough to carry on where the player left off
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// the name the puzzle was played under, like "starter#cross"
+    pub puzzle: String,
+    pub game: PicrossGame,
+    /// unplayed tiles are `TileState::Undetermined`
+    pub board: GameBoard,
+    /// includes the time added for mistakes
+    pub elapsed: Duration,
+    /// mistakes found by assisted play, which make the next one cost more
+    pub mistakes: usize,
+    /// wrong tiles already counted as mistakes, as x and y
+    pub flagged: Vec<(usize, usize)>,
+    /// the edits that can be undone, oldest first
+    pub history: Vec<Vec<TileChange>>,
+}
+
+fn tile_char(tile: &TileState) -> char {
+    match tile {
+        TileState::Filled => '#',
+        TileState::Empty => 'x',
+        TileState::Undetermined => '.',
+    }
+}
+
+fn tile_from_char(char: char) -> Option<TileState> {
+    match char {
+        '#' => Some(TileState::Filled),
+        'x' => Some(TileState::Empty),
+        '.' => Some(TileState::Undetermined),
+        _ => None,
+    }
+}
+
+fn parse_tile(token: &str) -> Option<(usize, usize)> {
+    let (x, y) = token.split_once(',')?;
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+fn parse_change(token: &str) -> Option<TileChange> {
+    let mut parts = token.split(',');
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    let mut tiles = parts.next()?.chars();
+    let from = tile_from_char(tiles.next()?)?;
+    let to = tile_from_char(tiles.next()?)?;
+    match (parts.next(), tiles.next()) {
+        (None, None) => Some(TileChange { x, y, from, to }),
+        _ => None,
+    }
+}
+
+enum Section {
+    Header,
+    Board,
+    Moves,
+}
+
+impl Session {
+    /// the session as text, with the board drawn one row per line
+    pub fn to_session_string(&self) -> String {
+        let mut lines = vec![
+            format!("puzzle: {}", self.puzzle),
+            format!("clues: {}", self.game.to_clues()),
+            format!("elapsed_ms: {}", self.elapsed.as_millis()),
+            format!("mistakes: {}", self.mistakes),
+        ];
+        if !self.flagged.is_empty() {
+            let tiles: Vec<String> = self
+                .flagged
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .collect();
+            lines.push(format!("flagged: {}", tiles.join(" ")));
+        }
+        lines.push("board:".to_string());
+        for row in self.board.rows() {
+            lines.push(row.iter().map(tile_char).collect());
+        }
+        lines.push("moves:".to_string());
+        for edit in self.history.iter() {
+            let changes: Vec<String> = edit
+                .iter()
+                .map(|change| {
+                    format!(
+                        "{},{},{}{}",
+                        change.x,
+                        change.y,
+                        tile_char(&change.from),
+                        tile_char(&change.to)
+                    )
+                })
+                .collect();
+            lines.push(changes.join(" "));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    pub fn from_session_string(text: &str) -> Result<Self, PicrossError> {
+        let mut puzzle = None;
+        let mut game = None;
+        let mut elapsed = None;
+        let mut mistakes = None;
+        let mut flagged = vec![];
+        let mut rows = vec![];
+        let mut history = vec![];
+        let mut section = Section::Header;
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |column: usize, token: &str, expected: &str| {
+                PicrossError::from(ParseError::new(line, line_number, column, token, expected))
+            };
+            match (&section, line.trim_end()) {
+                (_, "board:") => section = Section::Board,
+                (_, "moves:") => section = Section::Moves,
+                (_, "") => (),
+                (Section::Header, line) => {
+                    let Some((key, value)) = line.split_once(':') else {
+                        return Err(error(1, line, "\"key: value\""));
+                    };
+                    let column = key.len() + 2 + value.len() - value.trim_start().len();
+                    let value = value.trim();
+                    match key {
+                        "puzzle" => puzzle = Some(value.to_string()),
+                        "clues" => {
+                            game =
+                                Some(PicrossGame::from_clues(value).map_err(|_| {
+                                    error(column, value, "clues like \"1 1,1 / 2,0\"")
+                                })?)
+                        }
+                        "elapsed_ms" => {
+                            let millis = value
+                                .parse()
+                                .map_err(|_| error(column, value, "a number of milliseconds"))?;
+                            elapsed = Some(Duration::from_millis(millis))
+                        }
+                        "mistakes" => {
+                            mistakes = Some(
+                                value
+                                    .parse()
+                                    .map_err(|_| error(column, value, "a number of mistakes"))?,
+                            )
+                        }
+                        "flagged" => {
+                            for token in value.split_whitespace() {
+                                let column =
+                                    column + token.as_ptr() as usize - value.as_ptr() as usize;
+                                flagged.push(parse_tile(token).ok_or_else(|| {
+                                    error(column, token, "tiles like \"3,4\": x then y")
+                                })?);
+                            }
+                        }
+                        _ => {
+                            return Err(error(
+                                1,
+                                key,
+                                "puzzle, clues, elapsed_ms, mistakes or flagged",
+                            ))
+                        }
+                    }
+                }
+                (Section::Board, line) => {
+                    let row = line
+                        .chars()
+                        .enumerate()
+                        .map(|(column, char)| {
+                            tile_from_char(char).ok_or_else(|| {
+                                error(
+                                    column + 1,
+                                    &char.to_string(),
+                                    "\"#\" for filled, \"x\" for marked or \".\" for unplayed",
+                                )
+                            })
+                        })
+                        .collect::<Result<Vec<TileState>, PicrossError>>()?;
+                    rows.push(row);
+                }
+                (Section::Moves, line) => {
+                    let mut edit = vec![];
+                    for token in line.split_whitespace() {
+                        let column = token.as_ptr() as usize - line.as_ptr() as usize + 1;
+                        edit.push(
+                            parse_change(token)
+                                .ok_or_else(|| error(column, token, MOVES_EXPECTED))?,
+                        );
+                    }
+                    history.push(edit);
+                }
+            }
+        }
+        let missing = |key: &str| PicrossError::Format(format!("the session has no {}", key));
+        let game = game.ok_or_else(|| missing("clues"))?;
+        let board = GameBoard::from_rows(rows);
+        let ragged = board.rows().any(|row| row.len() != game.width());
+        if ragged || board.height() != game.height() {
+            return Err(PicrossError::DimensionMismatch {
+                expected: (game.width(), game.height()),
+                found: (board.width(), board.height()),
+            });
+        }
+        let outside = history
+            .iter()
+            .flatten()
+            .map(|change| ("move", change.x, change.y))
+            .chain(flagged.iter().map(|(x, y)| ("flagged tile", *x, *y)))
+            .find(|(_, x, y)| *x >= game.width() || *y >= game.height());
+        if let Some((what, x, y)) = outside {
+            return Err(PicrossError::Validation(format!(
+                "{} {},{} is outside the {}x{} board",
+                what,
+                x,
+                y,
+                game.width(),
+                game.height()
+            )));
+        }
+        Ok(Self {
+            puzzle: puzzle.ok_or_else(|| missing("puzzle"))?,
+            game,
+            board,
+            elapsed: elapsed.unwrap_or_default(),
+            mistakes: mistakes.unwrap_or_default(),
+            flagged,
+            history,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PicrossError> {
+        let name = path.display().to_string();
+        let text = std::fs::read_to_string(path).map_err(|error| PicrossError::io(&name, error))?;
+        Self::from_session_string(&text).map_err(|error| error.with_context(name))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PicrossError> {
+        std::fs::write(path, self.to_session_string())
+            .map_err(|error| PicrossError::io(&path.display().to_string(), error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let game = PicrossGame::from_rules("1,3,1", "1,3,1").unwrap();
+        let mut board = GameBoard::new(3, 3);
+        board.set_tile(1, 0, TileState::Filled).unwrap();
+        board.set_tile(0, 0, TileState::Empty).unwrap();
+        let session = Session {
+            puzzle: "starter#cross".to_string(),
+            game,
+            board,
+            elapsed: Duration::from_millis(83_250),
+            mistakes: 1,
+            flagged: vec![(0, 0)],
+            history: vec![
+                vec![
+                    TileChange {
+                        x: 0,
+                        y: 0,
+                        from: TileState::Undetermined,
+                        to: TileState::Filled,
+                    },
+                    TileChange {
+                        x: 1,
+                        y: 0,
+                        from: TileState::Undetermined,
+                        to: TileState::Filled,
+                    },
+                ],
+                vec![TileChange {
+                    x: 0,
+                    y: 0,
+                    from: TileState::Filled,
+                    to: TileState::Empty,
+                }],
+            ],
+        };
+        let text = session.to_session_string();
+        assert!(
+            text.contains("flagged: 0,0\nboard:\nx#.\n...\n...\nmoves:\n0,0,.# 1,0,.#\n0,0,#x\n")
+        );
+        assert_eq!(Session::from_session_string(&text).unwrap(), session);
+    }
+
+    #[test]
+    fn test_invalid_session() {
+        let text = "puzzle: dot\nclues: 1 / 1\nboard:\n#\nmoves:\n0,0,.# 0,0,?#\n";
+        let Err(PicrossError::Parse(error)) = Session::from_session_string(text) else {
+            panic!("? is not a tile");
+        };
+        assert_eq!((error.line, error.column), (6, 8));
+
+        let text = "puzzle: dot\nclues: 1 / 1\nboard:\n##\n";
+        assert!(matches!(
+            Session::from_session_string(text),
+            Err(PicrossError::DimensionMismatch { .. })
+        ));
+        let text = "puzzle: dot\nclues: 1 / 1\nboard:\n#\nmoves:\n1,0,.#\n";
+        assert!(Session::from_session_string(text).is_err());
+        let text = "puzzle: dot\nclues: 1 / 1\nflagged: 0,0 0\nboard:\n#\n";
+        let Err(PicrossError::Parse(error)) = Session::from_session_string(text) else {
+            panic!("0 is not a tile");
+        };
+        assert_eq!((error.line, error.column), (3, 14));
+        let text = "puzzle: dot\nclues: 1 / 1\nflagged: 0,1\nboard:\n#\n";
+        assert!(Session::from_session_string(text).is_err());
+    }
+}
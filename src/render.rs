@@ -1,15 +1,29 @@
-use std::{cmp, fmt::Display};
+use std::{
+    cmp,
+    fmt::Display,
+    io::{self, Write},
+    ops::Range,
+    thread,
+    time::{Duration, Instant},
+};
+
+use termion::{event::Key, input::TermRead, raw::IntoRawMode};
 
 use crate::{
-    game_board::GameBoard,
+    error::PicrossError,
+    export::asciicast::AsciicastRecorder,
+    game_board::{GameBoard, GameBoardRow, TileState},
     picross::{AxisRules, LineRule, PicrossGame},
 };
 
+/// how far along a board is towards solving its puzzle
 #[derive(PartialEq, Debug)]
 pub enum GameState {
-    #[allow(dead_code)]
+    /// the solver is still working on it
     InProgress,
+    /// the solver stopped without a board that fits every clue
     Invalid,
+    /// every tile is determined and fits the clues
     Complete,
 }
 
@@ -24,58 +38,285 @@ impl Display for GameState {
     }
 }
 
+/// a board together with its puzzle and state, as produced by the solvers
 pub struct PicrossFrame {
+    /// how far the solver got
     pub game_state: GameState,
     game: PicrossGame,
+    /// the tiles worked out so far
     pub board: GameBoard,
 }
 
-fn render_row_line_rule(rule: &LineRule) -> String {
+/// the digits of the biggest clue number in `rules`
+fn clue_digits(rules: &AxisRules) -> usize {
+    rules
+        .0
+        .iter()
+        .flat_map(|rule| rule.0.iter())
+        .map(|clue| clue.to_string().len())
+        .max()
+        .unwrap_or(1)
+}
+
+/// the clue numbers right aligned to `digits` each, so they line up with the
+/// other rows' and stay apart
+fn render_row_line_rule(rule: &LineRule, digits: usize) -> String {
     rule.0
         .iter()
-        .map(|chunk_size| chunk_size.to_string())
+        .map(|chunk_size| format!("{:>digits$}", chunk_size))
         .collect::<Vec<String>>()
         .join(" ")
 }
 
-fn render_column_axis_rules(rules: &AxisRules) -> String {
-    let row_count = rules
-        .0
+/// whether a guide line goes before tile `index`, one every 5 tiles of the
+/// board but not at the edge of what is shown
+fn guide_before(index: usize, start: usize, guides: bool) -> bool {
+    guides && index != start && index.is_multiple_of(5)
+}
+
+/// the clues of `columns`, bottom aligned and read from top to bottom, each
+/// column `tile_width` characters wide with at least a space after a number
+fn render_column_axis_rules(
+    rules: &AxisRules,
+    columns: &Range<usize>,
+    tile_width: usize,
+    guides: bool,
+) -> String {
+    let row_count = rules.0[columns.clone()]
         .iter()
         .fold(0, |acc, cur| cmp::max(acc, cur.0.len()));
+    let digits = tile_width - 1;
     (0..row_count)
-        .rev()
         .map(|row_index| {
-            rules
-                .0
-                .iter()
-                .map(|rule| {
-                    let row_rule = rule.0.get(row_index);
-                    match row_rule {
-                        Some(rule_number) => {
-                            let mut rule_string = rule_number.to_string();
-                            if rule_string.len() == 1 {
-                                rule_string.push_str(" ")
-                            }
-                            rule_string
-                        }
-                        None => "  ".to_string(),
-                    }
-                })
-                .collect::<String>()
+            let mut line = String::new();
+            for x in columns.clone() {
+                if guide_before(x, columns.start, guides) {
+                    line.push(' ');
+                }
+                let rule = &rules.0[x].0;
+                match (row_index + rule.len()).checked_sub(row_count) {
+                    Some(index) => line.push_str(&format!("{:>digits$} ", rule[index])),
+                    None => line.push_str(&" ".repeat(tile_width)),
+                }
+            }
+            line
         })
         .collect::<Vec<String>>()
         .join("\n")
 }
 
+/// where the clues go when a game is drawn as text, shared by everything
+/// that draws a board next to its clues
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClueLayout {
+    /// characters per column, the biggest column clue and a space
+    pub tile_width: usize,
+    /// characters per row clue number, the biggest row clue
+    pub row_digits: usize,
+    /// characters the row clues take left of the board
+    pub row_padding: usize,
+    /// lines the column clues take above the board
+    pub clue_height: usize,
+}
+
+impl ClueLayout {
+    /// the layout for the clues of `game`
+    pub fn new(game: &PicrossGame) -> Self {
+        let row_digits = clue_digits(&game.rows);
+        let row_padding = game
+            .rows
+            .0
+            .iter()
+            .map(|rule| render_row_line_rule(rule, row_digits).len())
+            .max()
+            .unwrap_or(0);
+        let clue_height = game.columns.0.iter().map(|rule| rule.0.len()).max();
+        Self {
+            tile_width: clue_digits(&game.columns) + 1,
+            row_digits,
+            row_padding,
+            clue_height: clue_height.unwrap_or(0),
+        }
+    }
+
+    /// the characters each number of a row's clues takes, counted from the
+    /// left of the line, as the numbers are right aligned to `row_padding`
+    pub fn row_clue_spans(&self, rule: &LineRule) -> Vec<Range<usize>> {
+        let slot = self.row_digits + 1;
+        let start = (self.row_padding + 1).saturating_sub(rule.0.len() * slot);
+        (0..rule.0.len())
+            .map(|index| start + index * slot..start + index * slot + self.row_digits)
+            .collect()
+    }
+
+    /// which of a column's clue numbers is on `line` of the column clues, as
+    /// they are bottom aligned
+    pub fn column_clue_index(&self, rule: &LineRule, line: usize) -> Option<usize> {
+        (line + rule.0.len()).checked_sub(self.clue_height)
+    }
+}
+
+fn render_tile(tile: &TileState, tile_width: usize) -> String {
+    let glyph = match tile {
+        TileState::Empty => " ",
+        TileState::Filled => "█",
+        TileState::Undetermined => "?",
+    };
+    glyph.repeat(tile_width)
+}
+
+/// the part of a board that fits in a terminal of `width` by `height`
+/// characters, scrolled to start at tile (`x`, `y`), with the clues of the
+/// rows and columns shown pinned beside it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// the first column of tiles shown
+    pub x: usize,
+    /// the first row of tiles shown
+    pub y: usize,
+    /// the terminal width in characters
+    pub width: usize,
+    /// the terminal height in lines
+    pub height: usize,
+    /// a character per tile and a line per two rows, leaving out the clues
+    pub compact: bool,
+    /// lines between every 5 rows and columns, not drawn when compact
+    pub guides: bool,
+}
+
+impl Viewport {
+    /// a viewport of `width` by `height` characters scrolled to the top left
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            compact: false,
+            guides: false,
+        }
+    }
+
+    /// big enough for any board
+    pub fn unlimited() -> Self {
+        Self::new(usize::MAX, usize::MAX)
+    }
+
+    /// the size of the terminal, or big enough for any board when stdout
+    /// isn't one
+    pub fn terminal() -> Self {
+        match termion::terminal_size() {
+            Ok((width, height)) => Self::new(width as usize, height as usize),
+            Err(_) => Self::unlimited(),
+        }
+    }
+
+    /// the columns and rows of a `board` sized board that are shown when
+    /// `margins` characters to the left and lines above are taken by clues,
+    /// and tiles are `tile_width` characters wide
+    pub fn visible(
+        &self,
+        board: (usize, usize),
+        margins: (usize, usize),
+        tile_width: usize,
+    ) -> (Range<usize>, Range<usize>) {
+        let (tile_width, rows_per_line, guides) = match self.compact {
+            true => (1, 2, false),
+            false => (tile_width, 1, self.guides),
+        };
+        let width = self.width.saturating_sub(margins.0);
+        let height = self
+            .height
+            .saturating_sub(margins.1)
+            .saturating_mul(rows_per_line);
+        // a guide takes a character or a line for every 5 tiles, plus one
+        // when the tiles shown don't start at a multiple of 5
+        let (columns, rows) = match guides {
+            true => (
+                width.saturating_sub(1).saturating_mul(5) / (5 * tile_width + 1),
+                height.saturating_sub(1).saturating_mul(5) / 6,
+            ),
+            false => (width / tile_width, height),
+        };
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let x = self.x.min(board.0.saturating_sub(columns));
+        let y = self.y.min(board.1.saturating_sub(rows));
+        (
+            x..x.saturating_add(columns).min(board.0),
+            y..y.saturating_add(rows).min(board.1),
+        )
+    }
+
+    /// scrolls as little as needed to show `tile`
+    pub fn scroll_to(
+        &mut self,
+        board: (usize, usize),
+        margins: (usize, usize),
+        tile_width: usize,
+        tile: (usize, usize),
+    ) {
+        let (columns, rows) = self.visible(board, margins, tile_width);
+        self.x = match tile.0 {
+            x if x < columns.start => x,
+            x if x >= columns.end => x + 1 - columns.len(),
+            _ => columns.start,
+        };
+        self.y = match tile.1 {
+            y if y < rows.start => y,
+            y if y >= rows.end => y + 1 - rows.len(),
+            _ => rows.start,
+        };
+    }
+
+    /// which columns and rows are shown, when they aren't all of them
+    pub fn describe(
+        columns: &Range<usize>,
+        rows: &Range<usize>,
+        board: (usize, usize),
+    ) -> Option<String> {
+        if columns.len() == board.0 && rows.len() == board.1 {
+            return None;
+        }
+        // padded so the text keeps its length while scrolling
+        let range = |range: &Range<usize>, size: usize| {
+            let digits = size.to_string().len();
+            format!(
+                "{:>digits$}-{:>digits$} of {}",
+                range.start + 1,
+                range.end,
+                size
+            )
+        };
+        Some(format!(
+            "columns {}, rows {}",
+            range(columns, board.0),
+            range(rows, board.1)
+        ))
+    }
+}
+
+/// the tiles of `board` in `columns` and `rows`
+fn crop(board: &GameBoard, columns: &Range<usize>, rows: &Range<usize>) -> GameBoard {
+    GameBoard(
+        board.0[rows.clone()]
+            .iter()
+            .map(|row| GameBoardRow(row.0[columns.clone()].to_vec()))
+            .collect(),
+    )
+}
+
 impl PicrossFrame {
+    /// a frame for `board`, failing when it isn't the size of `game`
     pub fn new(
         game: PicrossGame,
         board: GameBoard,
         game_state: GameState,
-    ) -> Result<Self, &'static str> {
+    ) -> Result<Self, PicrossError> {
         if game.width() != board.width() || game.height() != board.height() {
-            return Err("game dimensions don't match board dimensions");
+            return Err(PicrossError::DimensionMismatch {
+                expected: (game.width(), game.height()),
+                found: (board.width(), board.height()),
+            });
         }
 
         Ok(Self {
@@ -84,42 +325,363 @@ impl PicrossFrame {
             game_state,
         })
     }
+    /// the puzzle the board belongs to
+    pub fn game(&self) -> &PicrossGame {
+        &self.game
+    }
+    fn size(&self) -> (usize, usize) {
+        (self.game.width(), self.game.height())
+    }
+    fn tile_width(&self) -> usize {
+        ClueLayout::new(&self.game).tile_width
+    }
+    /// the characters left of the board and the lines above it
+    fn margins(&self, compact: bool) -> (usize, usize) {
+        match compact {
+            // the state and a blank line
+            true => (0, 2),
+            false => {
+                let layout = ClueLayout::new(&self.game);
+                (layout.row_padding, 2 + layout.clue_height)
+            }
+        }
+    }
+    /// the state line, the clues and the whole board
     pub fn render(&self) -> String {
-        let row_padding = self.game.rows.0.iter().fold(0, |acc, rule| {
-            let width = render_row_line_rule(&rule).len();
-            cmp::max(acc, width)
-        });
-        let column_rules_rendered = render_column_axis_rules(&self.game.columns);
+        let (width, height) = self.size();
+        self.render_tiles(0..width, 0..height, false)
+    }
+    /// the whole frame, with lines between every 5 rows and columns when
+    /// `guides` is set
+    pub fn render_with_guides(&self, guides: bool) -> String {
+        let (width, height) = self.size();
+        self.render_tiles(0..width, 0..height, guides)
+    }
+    /// the part of the frame `viewport` shows, keeping the clues of the
+    /// columns and rows shown, and saying which ones those are when the board
+    /// doesn't fit
+    pub fn render_viewport(&self, viewport: &Viewport) -> String {
+        let margins = self.margins(viewport.compact);
+        let (columns, rows) = viewport.visible(self.size(), margins, self.tile_width());
+        let mut rendered = match viewport.compact {
+            true => format!(
+                "{}\n\n{}",
+                self.game_state,
+                crop(&self.board, &columns, &rows).render_compact()
+            ),
+            false => self.render_tiles(columns.clone(), rows.clone(), viewport.guides),
+        };
+        if let Some(position) = Viewport::describe(&columns, &rows, self.size()) {
+            let state_length = self.game_state.to_string().len();
+            rendered.insert_str(state_length, &position);
+        }
+        rendered
+    }
+    fn render_tiles(&self, columns: Range<usize>, rows: Range<usize>, guides: bool) -> String {
+        let ClueLayout {
+            tile_width,
+            row_digits,
+            row_padding,
+            ..
+        } = ClueLayout::new(&self.game);
+        let column_rules_rendered =
+            render_column_axis_rules(&self.game.columns, &columns, tile_width, guides);
         let column_rules_rendered: String = column_rules_rendered
             .lines()
             .map(|line| " ".repeat(row_padding) + line)
             .collect::<Vec<String>>()
             .join("\n");
-        let board = &self.board.render();
-        let rows_and_board: String = self
-            .game
-            .rows
-            .0
-            .iter()
-            .zip(board.lines())
-            .map(|(rule, board_row)| {
-                let rule_rendered = render_row_line_rule(rule);
-                let padding = row_padding - rule_rendered.len();
-                format!("{}{}{}", " ".repeat(padding), rule_rendered, board_row)
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
+        let mut lines = vec![];
+        for y in rows.clone() {
+            if guide_before(y, rows.start, guides) {
+                let mut line = " ".repeat(row_padding);
+                for x in columns.clone() {
+                    if guide_before(x, columns.start, guides) {
+                        line.push('┼');
+                    }
+                    line.push_str(&"─".repeat(tile_width));
+                }
+                lines.push(line);
+            }
+            let rule_rendered = render_row_line_rule(&self.game.rows.0[y], row_digits);
+            let mut line = format!("{:>row_padding$}", rule_rendered);
+            for x in columns.clone() {
+                if guide_before(x, columns.start, guides) {
+                    line.push('│');
+                }
+                line.push_str(&render_tile(&self.board.0[y].0[x], tile_width));
+            }
+            lines.push(line);
+        }
         format!(
             "{}\n\n{}\n{}",
-            self.game_state, column_rules_rendered, rows_and_board
+            self.game_state,
+            column_rules_rendered,
+            lines.join("\n")
         )
     }
+    /// the escape sequences and render `print_viewport` writes to the
+    /// terminal
+    pub fn terminal_output(&self, clear: bool, viewport: &Viewport) -> String {
+        let clear = match clear {
+            true => termion::clear::All.to_string(),
+            false => String::new(),
+        };
+        format!(
+            "{}{}{}",
+            clear,
+            termion::cursor::Goto(1, 1),
+            self.render_viewport(viewport)
+        )
+    }
+    /// draws the frame over the terminal, cropped to fit it
     pub fn print(&self, clear: bool) {
-        let render = self.render();
-        if clear {
-            print!("{}", termion::clear::All);
+        self.print_viewport(clear, &Viewport::terminal());
+    }
+    /// draws the part of the frame `viewport` shows
+    pub fn print_viewport(&self, clear: bool, viewport: &Viewport) {
+        print!("{}", self.terminal_output(clear, viewport));
+    }
+}
+
+/// receives every intermediate frame a solver produces
+pub trait FrameObserver {
+    /// called with each frame, `clear` is set for the first one
+    fn on_frame(&mut self, frame: &PicrossFrame, clear: bool);
+    /// status text written around the solve, such as the elapsed time
+    fn on_text(&mut self, _text: &str) {}
+}
+
+/// animates the solve in the terminal
+pub struct TerminalPrinter;
+
+impl FrameObserver for TerminalPrinter {
+    fn on_frame(&mut self, frame: &PicrossFrame, clear: bool) {
+        frame.print(clear);
+    }
+    fn on_text(&mut self, text: &str) {
+        print!("{}", text);
+    }
+}
+
+/// the slowest `AnimationOptions::fps` worth asking for, a frame every 100
+/// seconds is slower than anyone would watch
+pub const MIN_FPS: f64 = 0.01;
+
+/// how `PacedPrinter` draws the animation
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationOptions {
+    /// the most frames drawn per second, unlimited when `None`
+    pub fps: Option<f64>,
+    /// draw only every nth solver step, the first frame is always drawn and
+    /// 0 draws nothing after it
+    pub every_n_steps: usize,
+    /// wait for a key press after each drawn frame
+    pub step: bool,
+    /// draw a character per tile without the clues, see `Viewport::compact`
+    pub compact: bool,
+    /// draw lines between every 5 rows and columns, see `Viewport::guides`
+    pub guides: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            fps: None,
+            every_n_steps: 1,
+            step: false,
+            compact: false,
+            guides: false,
+        }
+    }
+}
+
+/// animates the solve in the terminal at a readable pace, scrolling boards
+/// bigger than the terminal to follow the solver
+pub struct PacedPrinter {
+    options: AnimationOptions,
+    steps: usize,
+    last_drawn: Option<Instant>,
+    viewport: Viewport,
+    /// the board of the last frame drawn
+    previous: Option<GameBoard>,
+    /// gets everything drawn, exactly as it is written
+    recorder: Option<AsciicastRecorder>,
+    /// whether drawing writes to the terminal, off when only recording
+    echo: bool,
+}
+
+/// the first tile, row by row, that differs between the boards
+fn first_change(previous: &GameBoard, board: &GameBoard) -> Option<(usize, usize)> {
+    previous
+        .0
+        .iter()
+        .zip(&board.0)
+        .enumerate()
+        .find_map(|(y, (previous_row, row))| {
+            let x = previous_row
+                .0
+                .iter()
+                .zip(&row.0)
+                .position(|(a, b)| a != b)?;
+            Some((x, y))
+        })
+}
+
+impl PacedPrinter {
+    /// a printer pacing frames as `options` says, sized to the terminal
+    pub fn new(options: AnimationOptions) -> Self {
+        let viewport = Viewport {
+            compact: options.compact,
+            guides: options.guides,
+            ..Viewport::terminal()
+        };
+        Self {
+            options,
+            steps: 0,
+            last_drawn: None,
+            viewport,
+            previous: None,
+            recorder: None,
+            echo: true,
+        }
+    }
+
+    /// a printer that records everything it draws into `recorder`, writing
+    /// it to the terminal too when `echo` is set, steps only wait for a key
+    /// when it is
+    pub fn recording(options: AnimationOptions, recorder: AsciicastRecorder, echo: bool) -> Self {
+        Self {
+            recorder: Some(recorder),
+            echo,
+            ..Self::new(options)
+        }
+    }
+
+    /// the recording of everything drawn, `None` when not recording
+    pub fn into_recording(self) -> Option<AsciicastRecorder> {
+        self.recorder
+    }
+
+    /// writes `output` to the terminal and the recording
+    fn write(&mut self, output: &str) {
+        if self.echo {
+            print!("{}", output);
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(output);
+        }
+    }
+
+    /// counts a solver step, whether it is drawn: frames that clear the
+    /// screen and the first one always are, then every nth
+    fn should_draw(&mut self, clear: bool) -> bool {
+        self.steps += 1;
+        clear || (self.steps - 1).is_multiple_of(self.options.every_n_steps)
+    }
+
+    /// how long to wait at `now` before drawing to stay under the frame rate,
+    /// an interval too long for a `Duration` doesn't wait
+    fn wait(&self, now: Instant) -> Option<Duration> {
+        let interval = Duration::try_from_secs_f64(1.0 / self.options.fps?).ok()?;
+        interval.checked_sub(now.saturating_duration_since(self.last_drawn?))
+    }
+
+    /// fits the viewport to the terminal, which may have been resized, and
+    /// scrolls to the first tile changed since the last frame drawn
+    fn follow(&mut self, frame: &PicrossFrame) {
+        let terminal = Viewport::terminal();
+        self.viewport.width = terminal.width;
+        // the step prompt takes the last two lines
+        self.viewport.height = match self.options.step {
+            true => terminal.height.saturating_sub(2),
+            false => terminal.height,
+        };
+        let previous = self.previous.take();
+        let same_size = previous
+            .as_ref()
+            .map(|board| (board.width(), board.height()))
+            == Some(frame.size());
+        if !same_size {
+            (self.viewport.x, self.viewport.y) = (0, 0);
+        }
+        let changed = previous.and_then(|previous| first_change(&previous, &frame.board));
+        if let Some(tile) = changed {
+            let margins = frame.margins(self.viewport.compact);
+            let tile_width = frame.tile_width();
+            self.viewport
+                .scroll_to(frame.size(), margins, tile_width, tile);
+        }
+        self.previous = Some(frame.board.clone());
+    }
+
+    /// blocks until a key is pressed, arrows scroll the board, q, Esc or
+    /// Ctrl-C run the rest of the solve without stopping
+    fn wait_for_key(&mut self, frame: &PicrossFrame) {
+        loop {
+            self.write(&format!(
+                "\n\nstep {}: press any key to continue, arrows to scroll, q to run to the end",
+                self.steps
+            ));
+            let key = {
+                let Ok(mut stdout) = io::stdout().into_raw_mode() else {
+                    return;
+                };
+                let _ = stdout.flush();
+                io::stdin().keys().next()
+            };
+            let (dx, dy) = match key {
+                Some(Ok(Key::Left)) => (-1, 0),
+                Some(Ok(Key::Right)) => (1, 0),
+                Some(Ok(Key::Up)) => (0, -1),
+                Some(Ok(Key::Down)) => (0, 1),
+                Some(Ok(Key::Char('q') | Key::Esc | Key::Ctrl('c'))) => {
+                    self.options.step = false;
+                    return;
+                }
+                _ => return,
+            };
+            let margins = frame.margins(self.viewport.compact);
+            let (columns, rows) = self
+                .viewport
+                .visible(frame.size(), margins, frame.tile_width());
+            self.viewport.x = columns.start.saturating_add_signed(dx);
+            self.viewport.y = rows.start.saturating_add_signed(dy);
+            self.write(&frame.terminal_output(true, &self.viewport));
+        }
+    }
+}
+
+impl FrameObserver for PacedPrinter {
+    fn on_frame(&mut self, frame: &PicrossFrame, clear: bool) {
+        if !self.should_draw(clear) {
+            return;
+        }
+        if let Some(remaining) = self.wait(Instant::now()) {
+            thread::sleep(remaining);
+        }
+        self.last_drawn = Some(Instant::now());
+        self.follow(frame);
+        self.write(&frame.terminal_output(clear, &self.viewport));
+        if self.options.step && self.echo {
+            self.wait_for_key(frame);
+        }
+    }
+    fn on_text(&mut self, text: &str) {
+        self.write(text);
+    }
+}
+
+impl<'a> FrameObserver for Vec<&mut (dyn FrameObserver + 'a)> {
+    fn on_frame(&mut self, frame: &PicrossFrame, clear: bool) {
+        for observer in self.iter_mut() {
+            observer.on_frame(frame, clear);
+        }
+    }
+    fn on_text(&mut self, text: &str) {
+        for observer in self.iter_mut() {
+            observer.on_text(text);
         }
-        print!("{}{}", termion::cursor::Goto(1, 1), render);
     }
 }
 
@@ -138,15 +700,129 @@ mod tests {
     #[test]
     fn test_render_column_axis_rules() {
         let rules = AxisRules::from_str("1 1,1,1 1").unwrap();
-        let rendered = render_column_axis_rules(&rules);
+        let rendered = render_column_axis_rules(&rules, &(0..3), 2, false);
         let expected = "\
 1   1 
 1 1 1 ";
         assert_eq!(rendered, expected.to_string());
+
+        // read from top to bottom, with the numbers right aligned
+        let rules = AxisRules::from_str("1 2,10,3 12 1").unwrap();
+        let rendered = render_column_axis_rules(&rules, &(0..3), 3, false);
+        let expected = ["       3 ", " 1    12 ", " 2 10  1 "].join("\n");
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn test_clue_layout() {
+        let game = PicrossGame::from_rules("12,1 10", "2,1,2,2,2,2,2,2,2,2,2,2").unwrap();
+        let layout = ClueLayout::new(&game);
+        assert_eq!(
+            layout,
+            ClueLayout {
+                tile_width: 2,
+                row_digits: 2,
+                row_padding: 5,
+                clue_height: 1,
+            }
+        );
+        assert_eq!(layout.row_clue_spans(&game.rows.0[0]), vec![3..5]);
+        assert_eq!(layout.row_clue_spans(&game.rows.0[1]), vec![0..2, 3..5]);
+
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let layout = ClueLayout::new(&game);
+        assert_eq!(layout.column_clue_index(&game.columns.0[1], 0), None);
+        assert_eq!(layout.column_clue_index(&game.columns.0[1], 1), Some(0));
+    }
+
+    #[test]
+    fn test_multi_digit_clues() {
+        // the column numbers take as many characters as the biggest one and
+        // the row numbers line up on their digits
+        let game = PicrossGame::from_rules("12,1 10", "2,1,2,2,2,2,2,2,2,2,2,2").unwrap();
+        let frame = PicrossFrame::new(game, GameBoard::new(12, 2), GameState::InProgress).unwrap();
+        let expected = [
+            "In Progress    ",
+            "",
+            "     2 1 2 2 2 2 2 2 2 2 2 2 ",
+            "   12????????????????????????",
+            " 1 10????????????????????????",
+        ]
+        .join("\n");
+        assert_eq!(frame.render(), expected);
+
+        let game = PicrossGame::from_rules("2,1,2,2,2,2,2,2,2,2,2,2", "12,1 10").unwrap();
+        let frame = PicrossFrame::new(game, GameBoard::new(2, 12), GameState::InProgress).unwrap();
+        let expected = [
+            "In Progress    ",
+            "",
+            "     1 ",
+            " 12 10 ",
+            "2??????",
+            "1??????",
+        ]
+        .join("\n");
+        assert!(frame.render().starts_with(&expected));
     }
 
     #[test]
-    fn test_frame_produces_expected_output() {
+    fn test_guides() {
+        let game = PicrossGame::from_rules("6,1 1,1 1,1 1,1 1,6", "6,1 1,1 1,1 1,1 1,6").unwrap();
+        let frame = PicrossFrame::new(game, GameBoard::new(6, 6), GameState::InProgress).unwrap();
+        let viewport = Viewport {
+            guides: true,
+            ..Viewport::unlimited()
+        };
+        let expected = [
+            "In Progress    ",
+            "",
+            "     1 1 1 1    ",
+            "   6 1 1 1 1  6 ",
+            "  6??????????│??",
+            "1 1??????????│??",
+            "1 1??????????│??",
+            "1 1??????????│??",
+            "1 1??????????│??",
+            "   ──────────┼──",
+            "  6??????????│??",
+        ]
+        .join("\n");
+        assert_eq!(frame.render_viewport(&viewport), expected);
+        assert!(!frame.render().contains('│'));
+    }
+
+    #[test]
+    fn test_viewport() {
+        // 4 characters of clues on the left and 3 lines above leave room for
+        // 3 columns and 2 rows
+        let mut viewport = Viewport::new(10, 5);
+        assert_eq!(viewport.visible((8, 6), (4, 3), 2), (0..3, 0..2));
+        viewport.scroll_to((8, 6), (4, 3), 2, (4, 5));
+        assert_eq!(viewport.visible((8, 6), (4, 3), 2), (2..5, 4..6));
+        viewport.scroll_to((8, 6), (4, 3), 2, (3, 0));
+        assert_eq!(viewport.visible((8, 6), (4, 3), 2), (2..5, 0..2));
+        assert_eq!(
+            Viewport::describe(&(2..5), &(0..2), (8, 6)).unwrap(),
+            "columns 3-5 of 8, rows 1-2 of 6"
+        );
+        assert_eq!(Viewport::describe(&(0..8), &(0..6), (8, 6)), None);
+
+        let viewport = Viewport {
+            compact: true,
+            ..Viewport::new(10, 5)
+        };
+        assert_eq!(viewport.visible((20, 20), (0, 2), 2), (0..10, 0..6));
+
+        // a guide every 5 tiles takes a column and a line of its own
+        let viewport = Viewport {
+            guides: true,
+            ..Viewport::new(25, 15)
+        };
+        assert_eq!(viewport.visible((20, 20), (3, 2), 2), (0..9, 0..10));
+    }
+
+    #[test]
+    fn test_render_viewport() {
         let board = GameBoard(vec![
             GameBoardRow(vec![Filled, Empty, Filled]),
             GameBoardRow(vec![Empty, Filled, Empty]),
@@ -154,12 +830,101 @@ mod tests {
         ]);
         let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
         let frame = PicrossFrame::new(game, board, GameState::InProgress).unwrap();
-        let expected = "In Progress    \n
-   1   1 
-   1 1 1 
-1 1██  ██
-  1  ██  
-1 1██  ██";
-        assert_eq!(frame.render(), expected.to_string())
+        let viewport = Viewport {
+            x: 1,
+            y: 1,
+            ..Viewport::new(7, 6)
+        };
+        let expected = "In Progress    columns 2-3 of 3, rows 2-3 of 3\n
+     1 
+   1 1 
+  1██  
+1 1  ██";
+        assert_eq!(frame.render_viewport(&viewport), expected.to_string());
+        assert_eq!(
+            frame.render_viewport(&Viewport::new(100, 100)),
+            frame.render()
+        );
+
+        let compact = Viewport {
+            compact: true,
+            ..Viewport::new(100, 100)
+        };
+        assert_eq!(
+            frame.render_viewport(&compact),
+            "In Progress    \n\n▀▄▀\n▀ ▀"
+        );
+    }
+
+    #[test]
+    fn test_paced_printer_skips_steps() {
+        let mut printer = PacedPrinter::new(AnimationOptions {
+            every_n_steps: 3,
+            ..AnimationOptions::default()
+        });
+        let drawn: Vec<bool> = (0..7).map(|_| printer.should_draw(false)).collect();
+        assert_eq!(drawn, vec![true, false, false, true, false, false, true]);
+        assert!(printer.should_draw(true));
+
+        let mut printer = PacedPrinter::new(AnimationOptions {
+            every_n_steps: 0,
+            ..AnimationOptions::default()
+        });
+        assert!(printer.should_draw(false));
+        assert!(!printer.should_draw(false));
+        assert!(printer.should_draw(true));
+    }
+
+    #[test]
+    fn test_paced_printer_throttles() {
+        let mut printer = PacedPrinter::new(AnimationOptions {
+            fps: Some(4.0),
+            ..AnimationOptions::default()
+        });
+        let now = Instant::now();
+        // nothing drawn yet
+        assert_eq!(printer.wait(now), None);
+        printer.last_drawn = Some(now);
+        assert_eq!(printer.wait(now), Some(Duration::from_millis(250)));
+        assert_eq!(
+            printer.wait(now + Duration::from_millis(100)),
+            Some(Duration::from_millis(150))
+        );
+        assert_eq!(printer.wait(now + Duration::from_secs(1)), None);
+
+        printer.options.fps = Some(1e-20);
+        assert_eq!(printer.wait(now), None);
+        printer.options.fps = None;
+        assert_eq!(printer.wait(now), None);
+    }
+
+    #[test]
+    fn test_paced_printer_records_what_it_draws() {
+        let options = AnimationOptions {
+            every_n_steps: 2,
+            compact: true,
+            ..AnimationOptions::default()
+        };
+        let recorder = AsciicastRecorder::new(80, 24);
+        let mut printer = PacedPrinter::recording(options, recorder, false);
+        let game = PicrossGame::from_rules("2,0", "1,1").unwrap();
+        let board = GameBoard(vec![
+            GameBoardRow(vec![Filled, Filled]),
+            GameBoardRow(vec![Empty, Empty]),
+        ]);
+        let frame = PicrossFrame::new(game, board, GameState::Complete).unwrap();
+        for step in 0..3 {
+            printer.on_frame(&frame, step == 0);
+        }
+
+        let cast = printer.into_recording().unwrap().to_cast_string();
+        let frames: Vec<&str> = cast.lines().skip(1).collect();
+        // the second step is skipped, the others are drawn compact
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].contains("\\u001b[2J"));
+        for frame in frames {
+            assert!(frame.contains("Complete       \\r\\n\\r\\n▀▀"));
+            assert!(!frame.contains("████"));
+        }
     }
 }
@@ -0,0 +1,2 @@
+pub(crate) mod cdcl;
+pub(crate) mod cnf;
@@ -0,0 +1,583 @@
+use super::cnf::Cnf;
+
+/// variable index times two, plus one when negated
+type Literal = u32;
+
+const RESTART_BASE: usize = 100;
+const ACTIVITY_DECAY: f64 = 0.95;
+const ACTIVITY_LIMIT: f64 = 1e100;
+
+fn literal_from_dimacs(literal: i32) -> Literal {
+    (literal.unsigned_abs() - 1) * 2 + (literal < 0) as u32
+}
+
+fn variable(literal: Literal) -> usize {
+    (literal >> 1) as usize
+}
+
+fn is_negated(literal: Literal) -> bool {
+    literal & 1 == 1
+}
+
+/// the luby restart sequence 1, 1, 2, 1, 1, 2, 4, 1, ...
+fn luby(mut index: usize) -> usize {
+    let (mut size, mut sequence) = (1, 0);
+    while size < index + 1 {
+        sequence += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != index {
+        size = (size - 1) >> 1;
+        sequence -= 1;
+        index %= size;
+    }
+    1 << sequence
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SatResult {
+    /// every variable as a DIMACS literal, positive when true
+    Satisfiable(Vec<i32>),
+    Unsatisfiable,
+}
+
+/// the work a `CdclSolver` did, summed over every call to `solve`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SatStatistics {
+    /// variables assigned by guessing
+    pub decisions: usize,
+    /// variables assigned because a clause left them no choice
+    pub propagations: usize,
+    /// assignments that broke a clause and had to be undone
+    pub conflicts: usize,
+    /// times the solver dropped every decision and started over
+    pub restarts: usize,
+    /// clauses added to avoid a conflict coming back
+    pub learned_clauses: usize,
+}
+
+/// max heap of variables ordered by activity
+struct VariableHeap {
+    heap: Vec<usize>,
+    positions: Vec<Option<usize>>,
+}
+
+impl VariableHeap {
+    fn new(variable_count: usize) -> Self {
+        Self {
+            heap: (0..variable_count).collect(),
+            positions: (0..variable_count).map(Some).collect(),
+        }
+    }
+
+    fn contains(&self, variable: usize) -> bool {
+        self.positions[variable].is_some()
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a]] = Some(a);
+        self.positions[self.heap[b]] = Some(b);
+    }
+
+    fn sift_up(&mut self, mut index: usize, activity: &[f64]) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if activity[self.heap[parent]] >= activity[self.heap[index]] {
+                break;
+            }
+            self.swap(parent, index);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize, activity: &[f64]) {
+        loop {
+            let mut largest = index;
+            for child in [2 * index + 1, 2 * index + 2] {
+                if child < self.heap.len()
+                    && activity[self.heap[child]] > activity[self.heap[largest]]
+                {
+                    largest = child;
+                }
+            }
+            if largest == index {
+                break;
+            }
+            self.swap(largest, index);
+            index = largest;
+        }
+    }
+
+    fn insert(&mut self, variable: usize, activity: &[f64]) {
+        if self.contains(variable) {
+            return;
+        }
+        self.heap.push(variable);
+        self.positions[variable] = Some(self.heap.len() - 1);
+        self.sift_up(self.heap.len() - 1, activity);
+    }
+
+    fn increase(&mut self, variable: usize, activity: &[f64]) {
+        if let Some(index) = self.positions[variable] {
+            self.sift_up(index, activity);
+        }
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<usize> {
+        let top = *self.heap.first()?;
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        self.heap.pop();
+        self.positions[top] = None;
+        if !self.heap.is_empty() {
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+}
+
+/// conflict driven clause learning with two watched literals, first UIP
+/// learning, VSIDS decisions, phase saving and luby restarts
+pub(crate) struct CdclSolver {
+    clauses: Vec<Vec<Literal>>,
+    /// clauses watching each literal, visited when that literal becomes false
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<Literal>,
+    trail_limits: Vec<usize>,
+    propagation_head: usize,
+    activity: Vec<f64>,
+    activity_increment: f64,
+    heap: VariableHeap,
+    saved_phases: Vec<bool>,
+    seen: Vec<bool>,
+    is_unsatisfiable: bool,
+    pub statistics: SatStatistics,
+}
+
+impl CdclSolver {
+    pub(crate) fn new(cnf: &Cnf) -> Self {
+        let count = cnf.variable_count;
+        let mut solver = Self {
+            clauses: vec![],
+            watches: vec![vec![]; count * 2],
+            values: vec![None; count],
+            levels: vec![0; count],
+            reasons: vec![None; count],
+            trail: vec![],
+            trail_limits: vec![],
+            propagation_head: 0,
+            activity: vec![0.0; count],
+            activity_increment: 1.0,
+            heap: VariableHeap::new(count),
+            saved_phases: vec![false; count],
+            seen: vec![false; count],
+            is_unsatisfiable: false,
+            statistics: SatStatistics::default(),
+        };
+        for clause in cnf.clauses.iter() {
+            solver.add_clause(clause);
+        }
+        solver
+    }
+
+    /// the value of a DIMACS variable, fixed values are only guaranteed at
+    /// decision level zero
+    pub(crate) fn value(&self, variable: usize) -> Option<bool> {
+        self.values.get(variable.checked_sub(1)?).copied().flatten()
+    }
+
+    fn literal_value(&self, literal: Literal) -> Option<bool> {
+        self.values[variable(literal)].map(|value| value != is_negated(literal))
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_limits.len()
+    }
+
+    fn ensure_variable(&mut self, variable: usize) {
+        while self.values.len() <= variable {
+            let new_variable = self.values.len();
+            self.watches.extend([vec![], vec![]]);
+            self.values.push(None);
+            self.levels.push(0);
+            self.reasons.push(None);
+            self.activity.push(0.0);
+            self.heap.positions.push(None);
+            self.heap.insert(new_variable, &self.activity);
+            self.saved_phases.push(false);
+            self.seen.push(false);
+        }
+    }
+
+    /// adds a clause of DIMACS literals, only valid between calls to `solve`
+    pub(crate) fn add_clause(&mut self, clause: &[i32]) {
+        if self.is_unsatisfiable {
+            return;
+        }
+        let mut literals: Vec<Literal> = vec![];
+        for literal in clause.iter().filter(|literal| **literal != 0) {
+            let literal = literal_from_dimacs(*literal);
+            self.ensure_variable(variable(literal));
+            if literals.contains(&(literal ^ 1)) || self.literal_value(literal) == Some(true) {
+                return;
+            }
+            if !literals.contains(&literal) && self.literal_value(literal).is_none() {
+                literals.push(literal);
+            }
+        }
+        match literals.len() {
+            0 => self.is_unsatisfiable = true,
+            1 => {
+                self.enqueue(literals[0], None);
+                if self.propagate().is_some() {
+                    self.is_unsatisfiable = true;
+                }
+            }
+            _ => {
+                self.watches[literals[0] as usize].push(self.clauses.len());
+                self.watches[literals[1] as usize].push(self.clauses.len());
+                self.clauses.push(literals);
+            }
+        }
+    }
+
+    fn enqueue(&mut self, literal: Literal, reason: Option<usize>) {
+        let variable = variable(literal);
+        self.values[variable] = Some(!is_negated(literal));
+        self.levels[variable] = self.decision_level();
+        self.reasons[variable] = reason;
+        self.trail.push(literal);
+    }
+
+    /// returns the index of a conflicting clause
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagation_head < self.trail.len() {
+            let false_literal = self.trail[self.propagation_head] ^ 1;
+            self.propagation_head += 1;
+            self.statistics.propagations += 1;
+
+            let mut watchers = std::mem::take(&mut self.watches[false_literal as usize]);
+            let mut conflict = None;
+            let mut index = 0;
+            while index < watchers.len() {
+                let clause_index = watchers[index];
+                let clause = &mut self.clauses[clause_index];
+                if clause[0] == false_literal {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.values[variable(first)] == Some(!is_negated(first)) {
+                    index += 1;
+                    continue;
+                }
+                let replacement = (2..clause.len()).find(|position| {
+                    let literal = clause[*position];
+                    self.values[variable(literal)] != Some(is_negated(literal))
+                });
+                if let Some(position) = replacement {
+                    clause.swap(1, position);
+                    let new_watch = clause[1] as usize;
+                    self.watches[new_watch].push(clause_index);
+                    watchers.swap_remove(index);
+                    continue;
+                }
+                if self.literal_value(first) == Some(false) {
+                    conflict = Some(clause_index);
+                    break;
+                }
+                self.enqueue(first, Some(clause_index));
+                index += 1;
+            }
+            self.watches[false_literal as usize] = watchers;
+            if conflict.is_some() {
+                self.propagation_head = self.trail.len();
+                return conflict;
+            }
+        }
+        None
+    }
+
+    fn bump_activity(&mut self, variable: usize) {
+        self.activity[variable] += self.activity_increment;
+        if self.activity[variable] > ACTIVITY_LIMIT {
+            for activity in self.activity.iter_mut() {
+                *activity /= ACTIVITY_LIMIT;
+            }
+            self.activity_increment /= ACTIVITY_LIMIT;
+        }
+        self.heap.increase(variable, &self.activity);
+    }
+
+    /// derives the first UIP clause, its asserting literal comes first and
+    /// the literal with the highest remaining level second
+    fn analyze(&mut self, conflict: usize) -> (Vec<Literal>, usize) {
+        let mut learned: Vec<Literal> = vec![0];
+        let mut pending = 0;
+        let mut clause_index = conflict;
+        let mut trail_index = self.trail.len();
+        let mut implied: Option<Literal> = None;
+        loop {
+            let start = if implied.is_some() { 1 } else { 0 };
+            for position in start..self.clauses[clause_index].len() {
+                let literal = self.clauses[clause_index][position];
+                let variable = variable(literal);
+                if self.seen[variable] || self.levels[variable] == 0 {
+                    continue;
+                }
+                self.seen[variable] = true;
+                self.bump_activity(variable);
+                if self.levels[variable] == self.decision_level() {
+                    pending += 1;
+                } else {
+                    learned.push(literal);
+                }
+            }
+            loop {
+                trail_index -= 1;
+                if self.seen[variable(self.trail[trail_index])] {
+                    break;
+                }
+            }
+            let literal = self.trail[trail_index];
+            self.seen[variable(literal)] = false;
+            implied = Some(literal);
+            pending -= 1;
+            if pending == 0 {
+                break;
+            }
+            clause_index = self.reasons[variable(literal)].expect("implied literals have reasons");
+        }
+        learned[0] = implied.expect("the conflict has a literal at the current level") ^ 1;
+        for literal in learned.iter() {
+            self.seen[variable(*literal)] = false;
+        }
+
+        let mut backtrack_level = 0;
+        if learned.len() > 1 {
+            let (position, _) = learned
+                .iter()
+                .enumerate()
+                .skip(1)
+                .max_by_key(|(_, literal)| self.levels[variable(**literal)])
+                .expect("learned clause has more than one literal");
+            learned.swap(1, position);
+            backtrack_level = self.levels[variable(learned[1])];
+        }
+        (learned, backtrack_level)
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        let limit = self.trail_limits[level];
+        for literal in self.trail.drain(limit..) {
+            let variable = variable(literal);
+            self.values[variable] = None;
+            self.reasons[variable] = None;
+            self.saved_phases[variable] = !is_negated(literal);
+            self.heap.insert(variable, &self.activity);
+        }
+        self.trail_limits.truncate(level);
+        self.propagation_head = self.trail.len();
+    }
+
+    fn pick_decision(&mut self) -> Option<Literal> {
+        while let Some(variable) = self.heap.pop(&self.activity) {
+            if self.values[variable].is_none() {
+                let negated = !self.saved_phases[variable] as u32;
+                return Some(variable as u32 * 2 + negated);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn solve(&mut self) -> SatResult {
+        self.solve_with_progress(&mut |_| {})
+    }
+
+    /// `progress` is called after every restart, when only the facts proven
+    /// at decision level zero are assigned
+    pub(crate) fn solve_with_progress(
+        &mut self,
+        progress: &mut dyn FnMut(&CdclSolver),
+    ) -> SatResult {
+        if self.is_unsatisfiable || self.propagate().is_some() {
+            self.is_unsatisfiable = true;
+            return SatResult::Unsatisfiable;
+        }
+        let mut conflicts_until_restart = luby(self.statistics.restarts) * RESTART_BASE;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.statistics.conflicts += 1;
+                conflicts_until_restart = conflicts_until_restart.saturating_sub(1);
+                if self.decision_level() == 0 {
+                    self.is_unsatisfiable = true;
+                    return SatResult::Unsatisfiable;
+                }
+                let (learned, backtrack_level) = self.analyze(conflict);
+                self.backtrack(backtrack_level);
+                if learned.len() == 1 {
+                    self.enqueue(learned[0], None);
+                } else {
+                    let clause_index = self.clauses.len();
+                    self.watches[learned[0] as usize].push(clause_index);
+                    self.watches[learned[1] as usize].push(clause_index);
+                    let asserting = learned[0];
+                    self.clauses.push(learned);
+                    self.statistics.learned_clauses += 1;
+                    self.enqueue(asserting, Some(clause_index));
+                }
+                self.activity_increment /= ACTIVITY_DECAY;
+                continue;
+            }
+
+            if conflicts_until_restart == 0 {
+                self.statistics.restarts += 1;
+                conflicts_until_restart = luby(self.statistics.restarts) * RESTART_BASE;
+                self.backtrack(0);
+                progress(self);
+                continue;
+            }
+
+            match self.pick_decision() {
+                Some(literal) => {
+                    self.statistics.decisions += 1;
+                    self.trail_limits.push(self.trail.len());
+                    self.enqueue(literal, None);
+                }
+                None => {
+                    let model = self
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(variable, value)| match value {
+                            Some(false) => -(variable as i32 + 1),
+                            _ => variable as i32 + 1,
+                        })
+                        .collect();
+                    self.backtrack(0);
+                    return SatResult::Satisfiable(model);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        picross::PicrossGame,
+        sat::cnf::{board_from_model, encode_game},
+    };
+
+    fn satisfies(cnf: &Cnf, model: &[i32]) -> bool {
+        cnf.clauses
+            .iter()
+            .all(|clause| clause.iter().any(|literal| model.contains(literal)))
+    }
+
+    #[test]
+    fn test_luby() {
+        let sequence: Vec<usize> = (0..15).map(luby).collect();
+        assert_eq!(sequence, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn test_trivial_formulas() {
+        let cnf = Cnf {
+            variable_count: 1,
+            clauses: vec![vec![1], vec![-1]],
+        };
+        assert_eq!(CdclSolver::new(&cnf).solve(), SatResult::Unsatisfiable);
+
+        let cnf = Cnf {
+            variable_count: 3,
+            clauses: vec![vec![1, 2], vec![-1, 3], vec![-3, -2]],
+        };
+        match CdclSolver::new(&cnf).solve() {
+            SatResult::Satisfiable(model) => assert!(satisfies(&cnf, &model)),
+            SatResult::Unsatisfiable => panic!("formula is satisfiable"),
+        }
+    }
+
+    #[test]
+    fn test_pigeonhole_is_unsatisfiable() {
+        // four pigeons in three holes, variable p * 3 + h + 1
+        let pigeons = 4;
+        let holes = 3;
+        let mut cnf = Cnf {
+            variable_count: pigeons * holes,
+            clauses: vec![],
+        };
+        let var = |pigeon: usize, hole: usize| (pigeon * holes + hole + 1) as i32;
+        for pigeon in 0..pigeons {
+            cnf.add_clause((0..holes).map(|hole| var(pigeon, hole)).collect());
+        }
+        for hole in 0..holes {
+            for a in 0..pigeons {
+                for b in a + 1..pigeons {
+                    cnf.add_clause(vec![-var(a, hole), -var(b, hole)]);
+                }
+            }
+        }
+        let mut solver = CdclSolver::new(&cnf);
+        assert_eq!(solver.solve(), SatResult::Unsatisfiable);
+        assert!(solver.statistics.conflicts > 0);
+    }
+
+    #[test]
+    fn test_random_3_sat() {
+        let mut state = 7_u64;
+        let mut next = |limit: u64| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 33) % limit
+        };
+        for _ in 0..20 {
+            let variable_count = 40;
+            let mut cnf = Cnf {
+                variable_count,
+                clauses: vec![],
+            };
+            for _ in 0..160 {
+                let clause = (0..3)
+                    .map(|_| {
+                        let variable = next(variable_count as u64) as i32 + 1;
+                        if next(2) == 0 {
+                            variable
+                        } else {
+                            -variable
+                        }
+                    })
+                    .collect();
+                cnf.add_clause(clause);
+            }
+            if let SatResult::Satisfiable(model) = CdclSolver::new(&cnf).solve() {
+                assert!(satisfies(&cnf, &model));
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_blocking_clause() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let cnf = encode_game(&game);
+        let mut solver = CdclSolver::new(&cnf);
+        let SatResult::Satisfiable(model) = solver.solve() else {
+            panic!("puzzle is solvable");
+        };
+        let board = board_from_model(3, 3, &model);
+        assert_eq!(PicrossGame::from_board(&board).unwrap(), game);
+
+        let blocking: Vec<i32> = model.iter().take(9).map(|literal| -literal).collect();
+        solver.add_clause(&blocking);
+        assert_eq!(solver.solve(), SatResult::Unsatisfiable);
+    }
+}
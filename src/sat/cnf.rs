@@ -0,0 +1,396 @@
+use std::fmt::Write;
+
+use crate::{
+    error::PicrossError,
+    game_board::{GameBoard, GameBoardRow, TileState},
+    picross::PicrossGame,
+};
+
+/// a formula in conjunctive normal form, literals use the DIMACS convention of
+/// positive and negative variable numbers starting at 1
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Cnf {
+    pub variable_count: usize,
+    pub clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+    pub(crate) fn new_variable(&mut self) -> i32 {
+        self.variable_count += 1;
+        self.variable_count as i32
+    }
+
+    pub(crate) fn add_clause(&mut self, clause: Vec<i32>) {
+        self.clauses.push(clause);
+    }
+
+    pub(crate) fn to_dimacs(&self) -> String {
+        let mut output = format!("p cnf {} {}\n", self.variable_count, self.clauses.len());
+        for clause in self.clauses.iter() {
+            for literal in clause {
+                let _ = write!(output, "{} ", literal);
+            }
+            output.push_str("0\n");
+        }
+        output
+    }
+}
+
+/// the variable saying whether the tile at (x, y) is filled
+pub(crate) fn cell_variable(width: usize, x: usize, y: usize) -> i32 {
+    (y * width + x + 1) as i32
+}
+
+/// states of the automaton accepting `0* 1^b1 0+ 1^b2 ... 1^bk 0*`, laid out
+/// so every transition either loops on a gap state or moves to the next state
+struct LineAutomaton {
+    /// true for gap states, which are entered on an empty tile and loop on it
+    is_gap: Vec<bool>,
+    accepting: Vec<usize>,
+}
+
+impl LineAutomaton {
+    fn new(rule: &[usize]) -> Self {
+        let mut is_gap = vec![true];
+        for chunk in rule.iter().filter(|chunk| **chunk > 0) {
+            is_gap.extend(std::iter::repeat_n(false, *chunk));
+            is_gap.push(true);
+        }
+        let last = is_gap.len() - 1;
+        let accepting = match last {
+            0 => vec![0],
+            _ => vec![last - 1, last],
+        };
+        Self { is_gap, accepting }
+    }
+
+    fn last(&self) -> usize {
+        self.is_gap.len() - 1
+    }
+
+    fn transition(&self, state: usize, filled: bool) -> Option<usize> {
+        let next_is_gap = self.is_gap.get(state + 1).copied();
+        match (self.is_gap[state], filled) {
+            (true, false) => Some(state),
+            (_, true) => (next_is_gap == Some(false)).then_some(state + 1),
+            (false, false) => (next_is_gap == Some(true)).then_some(state + 1),
+        }
+    }
+
+    fn predecessors(&self, state: usize) -> Vec<usize> {
+        match (state, self.is_gap[state]) {
+            (0, _) => vec![0],
+            (_, true) => vec![state - 1, state],
+            (_, false) => vec![state - 1],
+        }
+    }
+
+    /// the range of tile counts after which the automaton can be in `state`
+    /// and still reach an accepting state by the end of a line of `length`
+    fn positions(&self, state: usize, length: usize) -> Option<(usize, usize)> {
+        let remaining = match self.accepting.contains(&state) {
+            true => 0,
+            false => self.last() - 1 - state,
+        };
+        let latest = length.checked_sub(remaining)?;
+        (state <= latest).then_some((state, latest))
+    }
+}
+
+/// adds clauses forcing `cells` to be laid out according to `rule`
+fn encode_line(cnf: &mut Cnf, cells: &[i32], rule: &[usize]) {
+    let automaton = LineAutomaton::new(rule);
+    let length = cells.len();
+    // states[i][state] is true when the automaton is in `state` after i tiles
+    let mut states: Vec<Vec<Option<i32>>> = vec![vec![None; automaton.is_gap.len()]; length + 1];
+    for state in 0..automaton.is_gap.len() {
+        if let Some((earliest, latest)) = automaton.positions(state, length) {
+            for position in states.iter_mut().take(latest + 1).skip(earliest) {
+                position[state] = Some(cnf.new_variable());
+            }
+        }
+    }
+
+    match states[0][0] {
+        Some(start) => cnf.add_clause(vec![start]),
+        None => {
+            cnf.add_clause(vec![]);
+            return;
+        }
+    }
+
+    for (index, cell) in cells.iter().enumerate() {
+        for state in 0..automaton.is_gap.len() {
+            let Some(current) = states[index][state] else {
+                continue;
+            };
+            for filled in [false, true] {
+                let tile = if filled { *cell } else { -cell };
+                match automaton
+                    .transition(state, filled)
+                    .and_then(|next| states[index + 1][next])
+                {
+                    Some(next) => cnf.add_clause(vec![-current, -tile, next]),
+                    None => cnf.add_clause(vec![-current, -tile]),
+                }
+            }
+        }
+        for state in 0..automaton.is_gap.len() {
+            let Some(next) = states[index + 1][state] else {
+                continue;
+            };
+            let entry_tile = if automaton.is_gap[state] {
+                -cell
+            } else {
+                *cell
+            };
+            cnf.add_clause(vec![-next, entry_tile]);
+            let mut support = vec![-next];
+            support.extend(
+                automaton
+                    .predecessors(state)
+                    .into_iter()
+                    .filter_map(|previous| states[index][previous]),
+            );
+            cnf.add_clause(support);
+        }
+    }
+
+    let accepted: Vec<i32> = automaton
+        .accepting
+        .iter()
+        .filter_map(|state| states[length][*state])
+        .collect();
+    cnf.add_clause(accepted);
+}
+
+/// encodes the puzzle so that the first width * height variables are the
+/// tiles in row major order, see `cell_variable`
+pub(crate) fn encode_game(game: &PicrossGame) -> Cnf {
+    let (width, height) = (game.width(), game.height());
+    let mut cnf = Cnf {
+        variable_count: width * height,
+        clauses: vec![],
+    };
+    for (y, rule) in game.rows.0.iter().enumerate() {
+        let cells: Vec<i32> = (0..width).map(|x| cell_variable(width, x, y)).collect();
+        encode_line(&mut cnf, &cells, &rule.0);
+    }
+    for (x, rule) in game.columns.0.iter().enumerate() {
+        let cells: Vec<i32> = (0..height).map(|y| cell_variable(width, x, y)).collect();
+        encode_line(&mut cnf, &cells, &rule.0);
+    }
+    cnf
+}
+
+/// the puzzle as a DIMACS CNF file for an external SAT solver, a tile is
+/// filled when its variable is true
+pub fn game_to_dimacs(game: &PicrossGame) -> String {
+    format!(
+        "c picross {}x{}, tile (x, y) is variable y * {} + x + 1\n{}",
+        game.width(),
+        game.height(),
+        game.width(),
+        encode_game(game).to_dimacs()
+    )
+}
+
+/// reads the literals of a model in either the MiniSat ("SAT" followed by
+/// literals) or the competition ("s SATISFIABLE" and "v" lines) format
+pub fn parse_model(input: &str) -> Result<Vec<i32>, PicrossError> {
+    let mut literals = vec![];
+    for line in input.lines().map(str::trim) {
+        let values = match line.split_once(' ').map_or((line, ""), |split| split) {
+            ("c", _) | ("", _) | ("SAT", _) | ("SATISFIABLE", _) => continue,
+            ("s", status) if status.trim() == "SATISFIABLE" => continue,
+            ("UNSAT", _) | ("UNSATISFIABLE", _) => {
+                return Err(PicrossError::Validation(
+                    "model is unsatisfiable".to_string(),
+                ))
+            }
+            ("s", status) => {
+                return Err(PicrossError::Validation(format!(
+                    "model is {}",
+                    status.trim().to_lowercase()
+                )))
+            }
+            ("v", values) => values,
+            _ => line,
+        };
+        for value in values.split_whitespace() {
+            let literal: i32 = value.parse().map_err(|_| {
+                PicrossError::Format(format!("invalid literal \"{}\" in model", value))
+            })?;
+            if literal != 0 {
+                literals.push(literal);
+            }
+        }
+    }
+    Ok(literals)
+}
+
+/// tiles whose variable is missing from the model stay undetermined, `0`
+/// literals end a clause in DIMACS and are skipped
+pub fn board_from_model(width: usize, height: usize, model: &[i32]) -> GameBoard {
+    let mut tiles = vec![TileState::Undetermined; width * height];
+    for literal in model {
+        let Some(index) = (literal.unsigned_abs() as usize).checked_sub(1) else {
+            continue;
+        };
+        if let Some(tile) = tiles.get_mut(index) {
+            *tile = match *literal > 0 {
+                true => TileState::Filled,
+                false => TileState::Empty,
+            };
+        }
+    }
+    GameBoard(
+        tiles
+            .chunks(width.max(1))
+            .take(height)
+            .map(|row| GameBoardRow(row.to_vec()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_board::TileState::*;
+
+    /// a tiny DPLL, only good enough to check encodings of small puzzles
+    fn is_satisfiable(clauses: &[Vec<i32>], assignment: &mut Vec<i32>) -> bool {
+        loop {
+            let mut unit = None;
+            for clause in clauses {
+                if clause.iter().any(|literal| assignment.contains(literal)) {
+                    continue;
+                }
+                let open: Vec<i32> = clause
+                    .iter()
+                    .copied()
+                    .filter(|literal| !assignment.contains(&-literal))
+                    .collect();
+                match open.len() {
+                    0 => return false,
+                    1 => {
+                        unit = Some(open[0]);
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+            match unit {
+                Some(literal) => assignment.push(literal),
+                None => break,
+            }
+        }
+        let open_literal = clauses
+            .iter()
+            .filter(|clause| !clause.iter().any(|literal| assignment.contains(literal)))
+            .flat_map(|clause| clause.iter())
+            .find(|literal| !assignment.contains(&-**literal));
+        match open_literal {
+            None => true,
+            Some(literal) => [*literal, -literal].into_iter().any(|choice| {
+                let mut branch = assignment.clone();
+                branch.push(choice);
+                is_satisfiable(clauses, &mut branch)
+            }),
+        }
+    }
+
+    #[test]
+    fn test_line_encoding_matches_rules() {
+        let rules: [&[usize]; 5] = [&[0], &[1], &[2], &[1, 1], &[1, 2]];
+        for rule in rules {
+            for bits in 0..(1 << 5) {
+                let tiles: Vec<TileState> = (0..5)
+                    .map(|x| if (bits >> x) & 1 == 1 { Filled } else { Empty })
+                    .collect();
+                let expected = GameBoard(vec![GameBoardRow(tiles.clone())])
+                    .get_row_chunks(0)
+                    .unwrap()
+                    == rule.to_vec();
+
+                let mut cnf = Cnf {
+                    variable_count: 5,
+                    clauses: vec![],
+                };
+                encode_line(&mut cnf, &[1, 2, 3, 4, 5], rule);
+                let mut assignment: Vec<i32> = tiles
+                    .iter()
+                    .enumerate()
+                    .map(|(x, tile)| match tile {
+                        Filled => x as i32 + 1,
+                        _ => -(x as i32 + 1),
+                    })
+                    .collect();
+                assert_eq!(
+                    is_satisfiable(&cnf.clauses, &mut assignment),
+                    expected,
+                    "rule {:?} tiles {:?}",
+                    rule,
+                    tiles
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_infeasible_line() {
+        let mut cnf = Cnf {
+            variable_count: 2,
+            clauses: vec![],
+        };
+        encode_line(&mut cnf, &[1, 2], &[1, 1]);
+        assert!(!is_satisfiable(&cnf.clauses, &mut vec![]));
+    }
+
+    #[test]
+    fn test_encode_game_solution() {
+        let game = PicrossGame::from_rules("1 1,1,1 1", "1 1,1,1 1").unwrap();
+        let cnf = encode_game(&game);
+        let mut assignment = vec![];
+        assert!(is_satisfiable(&cnf.clauses, &mut assignment));
+        let model: Vec<i32> = assignment
+            .into_iter()
+            .filter(|literal| literal.unsigned_abs() <= 9)
+            .collect();
+        let board = board_from_model(3, 3, &model);
+        assert_eq!(PicrossGame::from_board(&board).unwrap(), game);
+    }
+
+    #[test]
+    fn test_to_dimacs() {
+        let cnf = Cnf {
+            variable_count: 2,
+            clauses: vec![vec![1, -2], vec![2]],
+        };
+        assert_eq!(cnf.to_dimacs(), "p cnf 2 2\n1 -2 0\n2 0\n");
+    }
+
+    #[test]
+    fn test_parse_model() {
+        assert_eq!(parse_model("SAT\n1 -2 3 0\n").unwrap(), vec![1, -2, 3]);
+        assert_eq!(
+            parse_model("c comment\ns SATISFIABLE\nv 1 -2\nv 3 0\n").unwrap(),
+            vec![1, -2, 3]
+        );
+        assert!(parse_model("UNSAT\n").is_err());
+        assert!(parse_model("s UNSATISFIABLE\n").is_err());
+        assert!(parse_model("v 1 x 0").is_err());
+    }
+
+    #[test]
+    fn test_board_from_model() {
+        let board = board_from_model(2, 2, &[1, -2, 0, -3, 7]);
+        assert_eq!(
+            board,
+            GameBoard(vec![
+                GameBoardRow(vec![Filled, Empty]),
+                GameBoardRow(vec![Empty, Undetermined]),
+            ])
+        );
+    }
+}